@@ -0,0 +1,213 @@
+/// Log of `prior_val * likelihood_1 * ... * likelihood_to` for each hypothesis,
+/// summed in log space so long evidence chains don't underflow.
+pub fn log_recalculate_to(prior: &[f64], likelihoods: &[Vec<f64>], to: usize) -> Vec<f64> {
+    prior
+        .iter()
+        .enumerate()
+        .map(|(hyp_idx, prior_val)| {
+            likelihoods
+                .iter()
+                .take(to)
+                .fold(prior_val.ln(), |acc, ev| acc + ev[hyp_idx].ln())
+        })
+        .collect()
+}
+
+/// `ln(sum(exp(x)))`, shifted by the largest term to stay in range.
+pub fn log_sum_exp(logs: &[f64]) -> f64 {
+    let max = logs.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+    if max == f64::NEG_INFINITY {
+        return f64::NEG_INFINITY;
+    }
+    max + logs.iter().map(|x| (x - max).exp()).sum::<f64>().ln()
+}
+
+/// Turns log weights back into odds, scaled so that the largest is 1.
+/// Every weight is 0 if every hypothesis has been ruled out.
+pub(crate) fn exp_odds(logs: Vec<f64>) -> Vec<f64> {
+    let max = logs.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+    if max == f64::NEG_INFINITY {
+        return vec![0.0; logs.len()];
+    }
+    logs.iter().map(|x| (x - max).exp()).collect()
+}
+
+/// Multiplies each prior by the likelihoods of every evidence row.
+pub fn recalculate(prior: Vec<f64>, likelihoods: Vec<Vec<f64>>) -> Vec<f64> {
+    let to = likelihoods.len();
+    recalculate_to(prior, likelihoods, to)
+}
+
+/// Like `recalculate`, but only applies the first `to` evidence rows.
+pub fn recalculate_to(prior: Vec<f64>, likelihoods: Vec<Vec<f64>>, to: usize) -> Vec<f64> {
+    exp_odds(log_recalculate_to(&prior, &likelihoods, to))
+}
+
+/// True when there are hypotheses but none of them has any weight left, e.g.
+/// because each one has a 0% likelihood for some piece of evidence.
+pub fn ruled_out(odds: &[f64]) -> bool {
+    !odds.is_empty() && odds.iter().all(|x| *x == 0.0)
+}
+
+/// Scales odds so that they sum to 100.
+pub fn percentize(odds: Vec<f64>) -> Vec<f64> {
+    normalize(odds).iter().map(|x| 100.0 * x).collect()
+}
+
+/// Scales odds so that they sum to 1, or returns all zeros if they sum to 0.
+/// Odds are first shifted by the largest one (log-sum-exp style) so that very
+/// small or very large odds still normalise.
+pub fn normalize(odds: Vec<f64>) -> Vec<f64> {
+    let max = odds.iter().copied().fold(0.0, f64::max);
+    if max == 0.0 || !max.is_finite() {
+        return vec![0.0; odds.len()];
+    }
+    let total = odds.iter().map(|x| x / max).sum::<f64>();
+    odds.iter().map(|x| x / max / total).collect()
+}
+
+/// Log-odds of each hypothesis against all the others, in decibels. Worked
+/// out from log weights, so it stays finite however lopsided the odds are,
+/// until a hypothesis has no weight at all (−∞), or all the others don't (+∞).
+pub fn log_odds_in_db(odds: Vec<f64>) -> Vec<f64> {
+    let logs: Vec<f64> = odds.iter().map(|x| x.ln()).collect();
+    (0..logs.len())
+        .map(|hyp_idx| {
+            let others: Vec<f64> = logs
+                .iter()
+                .enumerate()
+                .filter(|(other_idx, _)| *other_idx != hyp_idx)
+                .map(|(_, log)| *log)
+                .collect();
+            10.0 * (logs[hyp_idx] - log_sum_exp(&others)) / std::f64::consts::LN_10
+        })
+        .collect()
+}
+
+/// Row `i`, column `j` is the log-odds of hypothesis `i` against hypothesis `j`,
+/// in decibels. For likelihoods this is the pairwise Bayes factor.
+pub fn pairwise_log_odds_in_db(odds: &[f64]) -> Vec<Vec<f64>> {
+    let logs: Vec<f64> = odds.iter().map(|x| x.ln()).collect();
+    pairwise_log_odds_in_db_from_logs(&logs)
+}
+
+/// As `pairwise_log_odds_in_db`, from natural-log weights such as those of
+/// `log_recalculate_to`, so that odds too small for an `f64` still compare.
+pub fn pairwise_log_odds_in_db_from_logs(logs: &[f64]) -> Vec<Vec<f64>> {
+    logs.iter()
+        .map(|a| {
+            logs.iter()
+                .map(|b| 10.0 * (a - b) / std::f64::consts::LN_10)
+                .collect()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(actual: &[f64], expected: &[f64]) {
+        assert_eq!(
+            actual.len(),
+            expected.len(),
+            "{:?} vs {:?}",
+            actual,
+            expected
+        );
+        for (a, e) in actual.iter().zip(expected) {
+            assert!((a - e).abs() < 1e-9, "{:?} vs {:?}", actual, expected);
+        }
+    }
+
+    #[test]
+    fn recalculate_multiplies_priors_by_likelihoods() {
+        let odds = recalculate(vec![1.0, 3.0], vec![vec![0.5, 0.25], vec![0.8, 0.4]]);
+        // 0.4 : 0.3, scaled so the largest is 1.
+        assert_close(&odds, &[1.0, 0.75]);
+    }
+
+    #[test]
+    fn recalculate_survives_long_evidence_chains() {
+        // The products underflow to 0 in linear space.
+        let likelihoods = vec![vec![1e-5, 2e-5]; 100];
+        let odds = recalculate(vec![1.0, 1.0], likelihoods);
+        assert_close(&odds, &[0.5f64.powi(100), 1.0]);
+        assert!(odds[0] > 0.0);
+    }
+
+    #[test]
+    fn recalculate_to_applies_only_the_first_rows() {
+        let prior = vec![1.0, 3.0];
+        let likelihoods = vec![vec![0.9, 0.1], vec![0.5, 0.5]];
+        assert_close(
+            &recalculate_to(prior.clone(), likelihoods.clone(), 0),
+            &[1.0 / 3.0, 1.0],
+        );
+        assert_close(
+            &recalculate_to(prior.clone(), likelihoods.clone(), 1),
+            &[1.0, 1.0 / 3.0],
+        );
+        assert_eq!(
+            recalculate_to(prior.clone(), likelihoods.clone(), 2),
+            recalculate(prior, likelihoods)
+        );
+    }
+
+    #[test]
+    fn recalculate_rules_out_every_hypothesis() {
+        let odds = recalculate(vec![1.0, 1.0], vec![vec![0.0, 0.0]]);
+        assert_eq!(odds, vec![0.0, 0.0]);
+        assert!(ruled_out(&odds));
+        assert!(!ruled_out(&[]));
+    }
+
+    #[test]
+    fn percentize_sums_to_100() {
+        assert_close(&percentize(vec![1.0, 3.0]), &[25.0, 75.0]);
+        assert_close(&percentize(vec![2.0, 2.0, 4.0]), &[25.0, 25.0, 50.0]);
+        assert_eq!(percentize(vec![0.0, 0.0]), vec![0.0, 0.0]);
+    }
+
+    #[test]
+    fn normalize_handles_tiny_and_degenerate_odds() {
+        assert_close(&normalize(vec![1e-300, 3e-300]), &[0.25, 0.75]);
+        assert_close(&normalize(vec![1e300, 1e300]), &[0.5, 0.5]);
+        assert_eq!(normalize(vec![0.0, 0.0]), vec![0.0, 0.0]);
+        assert_eq!(normalize(vec![f64::INFINITY, 1.0]), vec![0.0, 0.0]);
+        assert!(normalize(Vec::new()).is_empty());
+    }
+
+    #[test]
+    fn log_odds_in_db_is_against_all_other_hypotheses() {
+        assert_close(&log_odds_in_db(vec![1.0, 1.0]), &[0.0, 0.0]);
+        // 1 : 9 is -10 log10(9) dB.
+        let db = 10.0 * 9f64.log10();
+        assert_close(&log_odds_in_db(vec![1.0, 9.0]), &[-db, db]);
+        // A third of the weight against the other two thirds.
+        let db = 10.0 * 0.5f64.log10();
+        assert_close(&log_odds_in_db(vec![1.0, 1.0, 1.0]), &[db, db, db]);
+    }
+
+    #[test]
+    fn pairwise_log_odds_survive_long_evidence_chains() {
+        assert_close(&pairwise_log_odds_in_db(&[1.0, 10.0])[0], &[0.0, -10.0]);
+        // Both products underflow to 0 in linear space, 100 rows of 3 dB apart.
+        let logs = log_recalculate_to(&[1.0, 1.0], &vec![vec![1e-5, 2e-5]; 100], 100);
+        let db = 1000.0 * 2f64.log10();
+        let matrix = pairwise_log_odds_in_db_from_logs(&logs);
+        assert_close(&matrix[0], &[0.0, -db]);
+        assert_close(&matrix[1], &[db, 0.0]);
+    }
+
+    #[test]
+    fn log_odds_in_db_stays_finite_near_certainty() {
+        // Normalised, the first is exactly 1.0 and the second 1e-20.
+        assert_close(&log_odds_in_db(vec![1.0, 1e-20]), &[200.0, -200.0]);
+        assert_close(&log_odds_in_db(vec![1e-300, 1e-310]), &[100.0, -100.0]);
+        assert_eq!(
+            log_odds_in_db(vec![1.0, 0.0]),
+            vec![f64::INFINITY, f64::NEG_INFINITY]
+        );
+    }
+}
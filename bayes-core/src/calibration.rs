@@ -0,0 +1,240 @@
+use crate::calc::normalize;
+use serde::{Deserialize, Serialize};
+
+/// A resolved analysis: its posterior when it was resolved, and which
+/// hypothesis turned out to be true.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Prediction {
+    /// The library document it came from, if any.
+    pub document: Option<u64>,
+    pub name: String,
+    pub hypotheses: Vec<String>,
+    /// In percent, as in `BayesData::posterior_odds`.
+    pub posterior: Vec<f64>,
+    pub truth: usize,
+    /// Milliseconds since the Unix epoch.
+    pub resolved: f64,
+}
+
+impl Prediction {
+    /// The posterior as probabilities summing to 1.
+    pub fn probabilities(&self) -> Vec<f64> {
+        normalize(self.posterior.clone())
+    }
+
+    /// Multi-class Brier score: 0 is perfect, 2 is confidently wrong.
+    pub fn brier_score(&self) -> f64 {
+        self.probabilities()
+            .iter()
+            .enumerate()
+            .map(|(idx, p)| {
+                let outcome = if idx == self.truth { 1.0 } else { 0.0 };
+                (p - outcome) * (p - outcome)
+            })
+            .sum()
+    }
+
+    /// Natural log of the probability given to the truth: 0 is perfect.
+    pub fn log_score(&self) -> f64 {
+        self.probabilities()
+            .get(self.truth)
+            .copied()
+            .unwrap_or(0.0)
+            .ln()
+    }
+}
+
+/// One bin of a calibration curve.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CalibrationBin {
+    pub low: f64,
+    pub high: f64,
+    /// Mean forecast probability of the hypotheses in this bin.
+    pub forecast: f64,
+    /// How often those hypotheses were true.
+    pub frequency: f64,
+    pub count: usize,
+}
+
+/// Every resolved analysis, oldest first.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+pub struct PredictionLog {
+    pub predictions: Vec<Prediction>,
+}
+
+/// How close to a bin edge a probability counts as on it, so that e.g. a
+/// posterior of 10% normalised to 0.09999999999999999 is in the 0.1 bin.
+const BIN_EDGE_TOLERANCE: f64 = 1e-9;
+
+fn mean(values: impl Iterator<Item = f64>) -> Option<f64> {
+    let (sum, count) = values.fold((0.0, 0usize), |(sum, count), x| (sum + x, count + 1));
+    (count > 0).then(|| sum / count as f64)
+}
+
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+impl PredictionLog {
+    /// Adds a prediction, replacing any earlier resolution of the same document.
+    pub fn resolve(&mut self, prediction: Prediction) {
+        if prediction.document.is_some() {
+            self.predictions
+                .retain(|existing| existing.document != prediction.document);
+        }
+        self.predictions.push(prediction);
+    }
+
+    pub fn remove(&mut self, idx: usize) {
+        self.predictions.remove(idx);
+    }
+
+    pub fn unresolve(&mut self, id: u64) {
+        self.predictions
+            .retain(|prediction| prediction.document != Some(id));
+    }
+
+    pub fn for_document(&self, id: u64) -> Option<&Prediction> {
+        self.predictions
+            .iter()
+            .find(|prediction| prediction.document == Some(id))
+    }
+
+    pub fn brier_score(&self) -> Option<f64> {
+        mean(self.predictions.iter().map(Prediction::brier_score))
+    }
+
+    pub fn log_score(&self) -> Option<f64> {
+        mean(self.predictions.iter().map(Prediction::log_score))
+    }
+
+    /// Groups every (hypothesis, probability) forecast into `bins` equal-width
+    /// bins and compares the mean forecast with how often it came true. Each
+    /// bin holds its lower edge, and the last also 1. Empty bins are left out.
+    pub fn calibration(&self, bins: usize) -> Vec<CalibrationBin> {
+        let mut sums = vec![(0.0, 0.0, 0usize); bins];
+        for prediction in &self.predictions {
+            for (idx, p) in prediction.probabilities().iter().enumerate() {
+                let bin = ((p * bins as f64 + BIN_EDGE_TOLERANCE) as usize).min(bins - 1);
+                let outcome = if idx == prediction.truth { 1.0 } else { 0.0 };
+                sums[bin].0 += p;
+                sums[bin].1 += outcome;
+                sums[bin].2 += 1;
+            }
+        }
+        sums.iter()
+            .enumerate()
+            .filter(|(_, (_, _, count))| *count > 0)
+            .map(|(bin, (forecast, hits, count))| CalibrationBin {
+                low: bin as f64 / bins as f64,
+                high: (bin + 1) as f64 / bins as f64,
+                forecast: forecast / *count as f64,
+                frequency: hits / *count as f64,
+                count: *count,
+            })
+            .collect()
+    }
+
+    /// One row per hypothesis of every prediction.
+    pub fn to_csv(&self) -> String {
+        let mut csv = "analysis,resolved_ms,hypothesis,probability,true\n".to_string();
+        for prediction in &self.predictions {
+            for (idx, p) in prediction.probabilities().iter().enumerate() {
+                csv += &format!(
+                    "{},{},{},{},{}\n",
+                    csv_field(&prediction.name),
+                    prediction.resolved,
+                    csv_field(&prediction.hypotheses[idx]),
+                    p,
+                    idx == prediction.truth
+                );
+            }
+        }
+        csv
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn prediction(posterior: Vec<f64>, truth: usize) -> Prediction {
+        Prediction {
+            document: None,
+            name: "Test".to_string(),
+            hypotheses: (0..posterior.len()).map(|idx| idx.to_string()).collect(),
+            posterior,
+            truth,
+            resolved: 0.0,
+        }
+    }
+
+    fn log(predictions: Vec<Prediction>) -> PredictionLog {
+        PredictionLog { predictions }
+    }
+
+    #[test]
+    fn brier_score() {
+        assert!((prediction(vec![75.0, 25.0], 0).brier_score() - 0.125).abs() < 1e-12);
+        assert_eq!(prediction(vec![100.0, 0.0], 0).brier_score(), 0.0);
+        assert_eq!(prediction(vec![0.0, 100.0], 0).brier_score(), 2.0);
+        let both = log(vec![
+            prediction(vec![75.0, 25.0], 0),
+            prediction(vec![0.0, 100.0], 0),
+        ]);
+        assert!((both.brier_score().unwrap() - 1.0625).abs() < 1e-12);
+        assert_eq!(log(Vec::new()).brier_score(), None);
+    }
+
+    #[test]
+    fn log_score() {
+        assert!((prediction(vec![75.0, 25.0], 0).log_score() - 0.75f64.ln()).abs() < 1e-12);
+        assert_eq!(prediction(vec![100.0, 0.0], 0).log_score(), 0.0);
+        // Ruling out what turned out to be true is infinitely bad.
+        assert_eq!(
+            prediction(vec![100.0, 0.0], 1).log_score(),
+            f64::NEG_INFINITY
+        );
+        let both = log(vec![
+            prediction(vec![75.0, 25.0], 0),
+            prediction(vec![100.0, 0.0], 1),
+        ]);
+        assert_eq!(both.log_score(), Some(f64::NEG_INFINITY));
+    }
+
+    #[test]
+    fn probabilities_sum_to_1() {
+        let probabilities = prediction(vec![20.0, 30.0, 50.0], 0).probabilities();
+        assert!((probabilities.iter().sum::<f64>() - 1.0).abs() < 1e-12);
+        assert_eq!(
+            prediction(vec![0.0, 0.0], 0).probabilities(),
+            vec![0.0, 0.0]
+        );
+    }
+
+    #[test]
+    fn calibration_bins_hold_their_lower_edge() {
+        // Forecasts of 0%, 10%, ..., 100% and their complements, each on a
+        // bin edge.
+        let predictions = log((0..=10)
+            .map(|tenths| {
+                let p = 10.0 * tenths as f64;
+                prediction(vec![p, 100.0 - p], 0)
+            })
+            .collect());
+        let bins = predictions.calibration(10);
+        let counts: Vec<usize> = bins.iter().map(|bin| bin.count).collect();
+        // The last bin also gets the forecasts of 100%.
+        assert_eq!(counts, vec![2, 2, 2, 2, 2, 2, 2, 2, 2, 4]);
+        assert_eq!(bins[0].forecast, 0.0);
+        assert!((bins[1].forecast - 0.1).abs() < 1e-12);
+        assert!((bins[9].forecast - 0.95).abs() < 1e-12);
+        assert_eq!((bins[9].low, bins[9].high), (0.9, 1.0));
+        // Only the first hypotheses were true, at 0% and 100%.
+        assert_eq!((bins[0].frequency, bins[9].frequency), (0.5, 0.5));
+    }
+}
@@ -0,0 +1,493 @@
+use crate::calc::{percentize, recalculate};
+use crate::decimal::format_percent;
+use crate::diagnostic::{Diagnostic, ImportError, Imported, Located};
+use crate::markdown::{mark, unmark_label};
+use crate::model::BayesData;
+use crate::validate::{check_new_hypothesis, check_prior, parse_likelihood, parse_number};
+use std::borrow::Cow;
+
+const PRIOR_LABEL: &str = "Prior";
+
+/// One cell of a row.
+struct Cell<'a> {
+    /// The cell as written, quotes and all.
+    at: Located<'a>,
+    /// What's inside the quotes, still with `""` for a quote.
+    content: Located<'a>,
+    /// `content` with its quotes unescaped.
+    value: Cow<'a, str>,
+}
+
+/// Splits a line into cells at `delimiter`. A cell in double quotes may hold
+/// the delimiter, and `""` for a quote. `Err` is the rest of the line from a
+/// quote that's never closed.
+fn split_row(at: Located<'_>, delimiter: char) -> Result<Vec<Cell<'_>>, Located<'_>> {
+    let line = at.text;
+    let mut cells = Vec::new();
+    let mut start = 0;
+    loop {
+        let rest = &line[start..];
+        // Only spaces are skipped, as a tab may be the delimiter.
+        let (cell, end) = match rest.trim_start_matches(' ').strip_prefix('"') {
+            Some(quoted) => {
+                let open = line.len() - quoted.len();
+                let mut escaped = String::new();
+                let mut from = open;
+                let close = loop {
+                    let Some(quote) = line[from..].find('"') else {
+                        return Err(at.slice(start, line.len()).trim_start());
+                    };
+                    let quote = from + quote;
+                    if line[quote + 1..].starts_with('"') {
+                        escaped.push_str(&line[from..=quote]);
+                        from = quote + 2;
+                    } else {
+                        break quote;
+                    }
+                };
+                let mut value = if from == open {
+                    Cow::Borrowed(&line[open..close])
+                } else {
+                    Cow::Owned(escaped + &line[from..close])
+                };
+                if value.contains("\r\n") {
+                    value = Cow::Owned(value.replace("\r\n", "\n"));
+                }
+                let end = line[close..]
+                    .find(delimiter)
+                    .map_or(line.len(), |idx| close + idx);
+                let cell = Cell {
+                    at: at.slice(start, end).trim(),
+                    content: at.slice(open, close),
+                    value,
+                };
+                (cell, end)
+            }
+            None => {
+                let end = rest.find(delimiter).map_or(line.len(), |idx| start + idx);
+                let cell = at.slice(start, end).trim();
+                (
+                    Cell {
+                        at: cell,
+                        content: cell,
+                        value: Cow::Borrowed(cell.text),
+                    },
+                    end,
+                )
+            }
+        };
+        cells.push(cell);
+        if end == line.len() {
+            return Ok(cells);
+        }
+        start = end + delimiter.len_utf8();
+    }
+}
+
+/// Whether `line` ends inside a quoted cell, given whether it starts inside
+/// one. As in `split_row`, a quote only opens a cell after any spaces, and
+/// `""` inside one is a quote.
+fn ends_quoted(line: &str, delimiter: char, mut quoted: bool) -> bool {
+    let mut cell_start = !quoted;
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        if quoted {
+            if c == '"' && chars.next_if_eq(&'"').is_none() {
+                quoted = false;
+            }
+        } else if c == delimiter {
+            cell_start = true;
+        } else if cell_start && c == '"' {
+            quoted = true;
+            cell_start = false;
+        } else if c != ' ' {
+            cell_start = false;
+        }
+    }
+    quoted
+}
+
+/// Splits `content` into rows, skipping blank lines. A row is one line,
+/// unless a quoted cell holds a line break.
+fn read_rows(
+    content: &str,
+    delimiter: char,
+) -> Vec<(Located<'_>, Result<Vec<Cell<'_>>, Diagnostic>)> {
+    // Where each line starts and ends, without its line break.
+    let mut lines = Vec::new();
+    let mut start = 0;
+    for line in content.split_inclusive('\n') {
+        let text = line.strip_suffix('\n').unwrap_or(line);
+        let text = text.strip_suffix('\r').unwrap_or(text);
+        lines.push((start, start + text.len()));
+        start += line.len();
+    }
+    let located = |first: usize, last: usize| {
+        Located::line(first + 1, &content[lines[first].0..lines[last].1])
+    };
+
+    let mut rows = Vec::new();
+    let mut first = 0;
+    let mut unclosed = false;
+    while first < lines.len() {
+        if located(first, first).text.trim().is_empty() {
+            first += 1;
+            continue;
+        }
+        // A quote that isn't closed by the end of the line may be a line
+        // break in a cell, so the row runs on until it's closed. If it never
+        // is, it's reported on its own line, and from then on each line is a
+        // row, so the rest of the file is only looked through once more.
+        let mut last = first;
+        if !unclosed {
+            let line = |idx: usize| located(idx, idx).text;
+            let mut quoted = ends_quoted(line(first), delimiter, false);
+            while quoted && last + 1 < lines.len() {
+                last += 1;
+                quoted = ends_quoted(line(last), delimiter, true);
+            }
+            if quoted {
+                last = first;
+                unclosed = true;
+            }
+        }
+        let at = located(first, last);
+        rows.push((at, read_row(at, delimiter)));
+        first = last + 1;
+    }
+    rows
+}
+
+/// Reads a row, dropping the empty cells spreadsheets pad rows out with.
+fn read_row<'a>(at: Located<'a>, delimiter: char) -> Result<Vec<Cell<'a>>, Diagnostic> {
+    let mut cells = split_row(at, delimiter).map_err(|rest| {
+        rest.error(
+            "This quote is never closed",
+            "Close the cell with another \", and write \"\" for a quote inside it",
+        )
+    })?;
+    while cells.len() > 1
+        && cells
+            .last()
+            .is_some_and(|cell| cell.value.trim().is_empty())
+    {
+        cells.pop();
+    }
+    Ok(cells)
+}
+
+/// Reads each of a row's values with `parse`, on its own so that every bad
+/// cell is reported in `diagnostics`.
+fn read_values(
+    values: &[Cell<'_>],
+    parse: impl Fn(Located) -> Result<f64, Diagnostic>,
+    diagnostics: &mut Vec<Diagnostic>,
+) -> Vec<f64> {
+    values
+        .iter()
+        .filter_map(|cell| {
+            // Parsed as written, as a number has no quotes to unescape.
+            let content = cell.content.trim();
+            let result = if content.text.is_empty() {
+                Err(cell
+                    .at
+                    .error("This cell is empty", "Fill in a value for each hypothesis"))
+            } else {
+                parse(content)
+            };
+            result
+                .map_err(|diagnostic| diagnostics.push(diagnostic))
+                .ok()
+        })
+        .collect()
+}
+
+fn quote(cell: &str, delimiter: char) -> String {
+    if cell.contains([delimiter, '"', '\n', '\r']) || cell.trim() != cell {
+        format!("\"{}\"", cell.replace('"', "\"\""))
+    } else {
+        cell.to_string()
+    }
+}
+
+/// The likelihood matrix as CSV, or TSV with a tab `delimiter`: hypotheses
+/// as columns, a first row of priors, then a row of likelihoods per piece of
+/// evidence, marked up as inactive or planned as in markdown but not escaped,
+/// as a spreadsheet shows labels as they are. Uncertainty,
+/// decisions and the outcomes of categorical and continuous evidence aren't
+/// included; their rows hold the likelihood of what was observed.
+pub fn to_csv(data: &BayesData, delimiter: char) -> String {
+    let row = |label: &str, cells: Vec<String>| {
+        std::iter::once(quote(label, delimiter))
+            .chain(cells)
+            .collect::<Vec<String>>()
+            .join(&delimiter.to_string())
+            + "\n"
+    };
+    let mut csv = row(
+        "Evidence",
+        data.hypotheses
+            .iter()
+            .map(|hypothesis| quote(hypothesis, delimiter))
+            .collect(),
+    );
+    csv += &row(
+        PRIOR_LABEL,
+        data.prior_odds.iter().map(f64::to_string).collect(),
+    );
+    for (ev_idx, likelihoods) in data.likelihoods.iter().enumerate() {
+        csv += &row(
+            &mark(data.evidence[ev_idx].clone(), data, ev_idx),
+            likelihoods
+                .iter()
+                .map(|p| format!("{}%", format_percent(*p)))
+                .collect(),
+        );
+    }
+    csv
+}
+
+/// The inverse of `to_csv`. Likelihoods are percentages, as in markdown,
+/// whether or not they have a `%`. Only the row after the header holds the
+/// priors, so any later row is evidence, even one labelled 'Prior'.
+pub fn parse_csv(content: &str, delimiter: char) -> Result<Imported, ImportError> {
+    let mut rows = read_rows(content, delimiter).into_iter();
+    let (header_at, header) = rows.next().ok_or_else(|| {
+        Diagnostic::whole_file(
+            "The file is empty",
+            "Start with a header row: a label like 'Evidence', then the hypotheses",
+        )
+    })?;
+    let header = header?;
+    if header.len() < 2 {
+        return Err(header_at
+            .error(
+                "The header row has no hypotheses",
+                "Put the hypothesis names in the first row, after a label like 'Evidence'",
+            )
+            .into());
+    }
+
+    let mut diagnostics = Vec::new();
+    let mut hypotheses: Vec<&str> = Vec::new();
+    for cell in &header[1..] {
+        let at = cell.at;
+        let name = cell.value.trim();
+        if name.is_empty() {
+            diagnostics.push(at.error(
+                "This hypothesis has no name",
+                "Give every column after the first a hypothesis name",
+            ));
+        } else if let Err(invalid) = check_new_hypothesis(&hypotheses, name) {
+            diagnostics.push(at.invalid(invalid));
+        }
+        hypotheses.push(name);
+    }
+    let count = hypotheses.len();
+
+    let mut prior_odds: Option<Vec<f64>> = None;
+    let mut evidence = Vec::new();
+    let mut likelihoods = Vec::new();
+    let mut active = Vec::new();
+    let mut planned = Vec::new();
+    for (row_idx, (_, cells)) in rows.enumerate() {
+        let cells = match cells {
+            Ok(cells) => cells,
+            Err(diagnostic) => {
+                diagnostics.push(diagnostic);
+                continue;
+            }
+        };
+        let label = cells[0].value.trim();
+        let values = &cells[1..];
+        if values.len() != count {
+            let fix = if values.len() < count {
+                "Add a value for each hypothesis, in the same order as the header"
+            } else {
+                "Remove the extra cells, or add the hypotheses they're for to the header"
+            };
+            diagnostics.push(cells.last().unwrap().at.error(
+                format!(
+                    "This row has {} value{}, but there are {} hypotheses",
+                    values.len(),
+                    if values.len() == 1 { "" } else { "s" },
+                    count
+                ),
+                fix,
+            ));
+        }
+        if row_idx == 0 && label.eq_ignore_ascii_case(PRIOR_LABEL) {
+            let parse_prior = |at: Located| {
+                let prior = parse_number(at, "1")?;
+                check_prior(prior).map_err(|invalid| at.invalid(invalid))?;
+                Ok(prior)
+            };
+            prior_odds = Some(read_values(values, parse_prior, &mut diagnostics));
+            continue;
+        }
+        if row_idx == 0 {
+            diagnostics.push(cells[0].at.error(
+                "The row after the header should be the priors",
+                "Add a row labelled 'Prior' with each hypothesis's prior under it",
+            ));
+            // Only said once, for the first row.
+            prior_odds.get_or_insert_with(Vec::new);
+        }
+        let (label, inactive, is_planned) = unmark_label(label, false);
+        evidence.push(label);
+        active.push(!inactive);
+        planned.push(is_planned);
+        likelihoods.push(read_values(values, parse_likelihood, &mut diagnostics));
+    }
+    if prior_odds.is_none() {
+        diagnostics.push(Diagnostic::whole_file(
+            "There are no priors",
+            "Add a row labelled 'Prior' after the header, with each hypothesis's prior",
+        ));
+    }
+    if !diagnostics.is_empty() {
+        diagnostics.sort_by_key(|diagnostic| (diagnostic.line, diagnostic.column));
+        return Err(ImportError { diagnostics });
+    }
+
+    let mut data = BayesData {
+        hypotheses: hypotheses.iter().map(|name| name.to_string()).collect(),
+        prior_odds: prior_odds.unwrap_or_default(),
+        posterior_odds: Vec::new(),
+        evidence,
+        likelihoods,
+        active,
+        prior_spread: Vec::new(),
+        likelihood_spread: Vec::new(),
+        kinds: Vec::new(),
+        planned,
+        actions: Vec::new(),
+        utilities: Vec::new(),
+        entry_modes: Vec::new(),
+    };
+    data.posterior_odds = percentize(recalculate(
+        data.prior_odds.clone(),
+        data.effective_likelihoods(),
+    ));
+    Ok(Imported {
+        data,
+        warnings: Vec::new(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::format::FileFormat;
+
+    #[test]
+    fn round_trips() {
+        let data = BayesData {
+            hypotheses: vec![
+                "Rain, \"heavy\"".to_string(),
+                "Semi; colon".to_string(),
+                "Two\nlines".to_string(),
+            ],
+            prior_odds: vec![1.0, 0.1, 3.5],
+            evidence: vec![
+                "Clouds".to_string(),
+                "Wet\tgrass".to_string(),
+                "Prior".to_string(),
+            ],
+            likelihoods: vec![
+                vec![0.9, 0.35, 1.0 / 3.0],
+                vec![0.07, 0.125, 0.0],
+                vec![0.5, 0.5, 0.5],
+            ],
+            active: vec![true, false, true],
+            planned: vec![true, false, false],
+            ..BayesData::default()
+        };
+        for delimiter in [',', '\t'] {
+            let imported = parse_csv(&to_csv(&data, delimiter), delimiter).unwrap();
+            assert!(imported.warnings.is_empty());
+            let back = imported.data;
+            assert_eq!(back.hypotheses, data.hypotheses);
+            assert_eq!(back.prior_odds, data.prior_odds);
+            assert_eq!(back.evidence, data.evidence);
+            assert_eq!(back.likelihoods, data.likelihoods);
+            assert_eq!(back.active, data.active);
+            assert_eq!(back.planned, data.planned);
+        }
+    }
+
+    #[test]
+    fn labels_are_not_escaped() {
+        let data = BayesData {
+            evidence: vec!["snake_case {x} #1".to_string()],
+            ..BayesData::default()
+        };
+        let csv = to_csv(&data, ',');
+        assert!(csv.contains("\nsnake_case {x} #1,50%,50%\n"));
+        assert_eq!(parse_csv(&csv, ',').unwrap().data.evidence, data.evidence);
+    }
+
+    #[test]
+    fn quoted_cells_hold_delimiters_quotes_and_line_breaks() {
+        let text = "Evidence,\"A, or \"\"B\"\"\",\"C\r\nD\"\r\nPrior,1,1\r\n\
+                    \"Long\r\n\r\nlabel\",50%,\"25%\"\r\n";
+        let data = parse_csv(text, ',').unwrap().data;
+        assert_eq!(data.hypotheses, vec!["A, or \"B\"", "C\nD"]);
+        assert_eq!(data.evidence, vec!["Long\n\nlabel"]);
+        assert_eq!(data.likelihoods, vec![vec![0.5, 0.25]]);
+    }
+
+    #[test]
+    fn reads_tsv() {
+        assert_eq!(
+            FileFormat::from_file_name("analysis.tsv"),
+            Some(FileFormat::Tsv)
+        );
+        assert_eq!(
+            FileFormat::from_file_name("analysis.csv"),
+            Some(FileFormat::Csv)
+        );
+        // Commas are only delimiters in CSV.
+        let text = "Evidence\tA, B\tC\nPrior\t1\t2\nE\t50%\t25\n";
+        let data = FileFormat::Tsv.parse(text).unwrap().data;
+        assert_eq!(data.hypotheses, vec!["A, B", "C"]);
+        assert_eq!(data.prior_odds, vec![1.0, 2.0]);
+        assert_eq!(data.likelihoods, vec![vec![0.5, 0.25]]);
+    }
+
+    #[test]
+    fn places_non_numeric_cells() {
+        let text = "Evidence,A,B\n\nPrior,1,one\nE1,50%,  half \n";
+        assert_eq!(
+            parse_csv(text, ',').unwrap_err().positions(),
+            vec![(3, 9, "one".to_string()), (4, 10, "half".to_string())]
+        );
+        // After a line break in a cell, cells are placed on the line they're on.
+        let text = "Evidence,A,B\nPrior,1,1\n\"Two\nlines\",5O%,\"x\"\n";
+        assert_eq!(
+            parse_csv(text, ',').unwrap_err().positions(),
+            vec![(4, 8, "5O%".to_string()), (4, 13, "x".to_string())]
+        );
+        // A quote that's never closed is reported on its own line, and the
+        // lines after it are rows of their own.
+        let text = "Evidence,A\nPrior,\"1\nE,50%\nF,x\n";
+        assert_eq!(
+            parse_csv(text, ',').unwrap_err().positions(),
+            vec![
+                (0, 0, String::new()),
+                (2, 7, "\"1".to_string()),
+                (4, 3, "x".to_string())
+            ]
+        );
+    }
+
+    #[test]
+    fn places_errors_inside_quoted_cells() {
+        let text =
+            "Evidence,\"Say \"\"hi\"\"\",B\nPrior,\"1\",  x\n\"E \"\"1\"\"\",  \"5O%\" ,50%\n";
+        assert_eq!(
+            parse_csv(text, ',').unwrap_err().positions(),
+            vec![(2, 13, "x".to_string()), (3, 14, "5O%".to_string())]
+        );
+    }
+}
@@ -0,0 +1,103 @@
+//! Percentages written and read by moving the decimal point in the text, so
+//! that a probability survives being written out as a percentage and read
+//! back bit for bit, which multiplying and dividing by 100 doesn't promise.
+
+use std::num::ParseFloatError;
+use std::str::FromStr;
+
+/// Moves the decimal point of a plain decimal like `-12.5` by `places` to the
+/// right (to the left if negative). `None` for anything else, e.g. `1e-5`.
+fn shift_decimal(text: &str, places: i32) -> Option<String> {
+    let (sign, unsigned) = match text.strip_prefix('-') {
+        Some(rest) => ("-", rest),
+        None => ("", text.strip_prefix('+').unwrap_or(text)),
+    };
+    let (int, frac) = unsigned.split_once('.').unwrap_or((unsigned, ""));
+    if int.is_empty() && frac.is_empty()
+        || !int.chars().chain(frac.chars()).all(|c| c.is_ascii_digit())
+    {
+        return None;
+    }
+    let digits = format!("{}{}", int, frac);
+    let point = int.len() as i32 + places;
+    let (int, frac) = if point <= 0 {
+        (String::new(), "0".repeat((-point) as usize) + &digits)
+    } else if point as usize >= digits.len() {
+        (
+            digits.clone() + &"0".repeat(point as usize - digits.len()),
+            String::new(),
+        )
+    } else {
+        (
+            digits[..point as usize].to_string(),
+            digits[point as usize..].to_string(),
+        )
+    };
+    let int = int.trim_start_matches('0');
+    let frac = frac.trim_end_matches('0');
+    let int = if int.is_empty() { "0" } else { int };
+    Some(if frac.is_empty() {
+        format!("{}{}", sign, int)
+    } else {
+        format!("{}{}.{}", sign, int, frac)
+    })
+}
+
+/// A probability as a percentage without the `%`, e.g. `0.125` as `12.5`.
+pub(crate) fn format_percent(p: f64) -> String {
+    // `Display` for floats is the shortest text that reads back exactly, and
+    // never uses an exponent.
+    let text = p.to_string();
+    shift_decimal(&text, 2).unwrap_or_else(|| (100.0 * p).to_string())
+}
+
+/// The inverse of `format_percent`. Anything that isn't a plain decimal, such
+/// as `1e-3`, is read as a float and divided by 100.
+pub(crate) fn parse_percent(text: &str) -> Result<f64, ParseFloatError> {
+    let text = text.trim();
+    match shift_decimal(text, -2) {
+        Some(shifted) => f64::from_str(&shifted),
+        None => Ok(0.01 * f64::from_str(text)?),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn percentages_round_trip_exactly() {
+        for p in [
+            0.1,
+            0.07,
+            1.0 / 3.0,
+            2.0 / 3.0,
+            1e-300,
+            5e-324,
+            0.125,
+            1.0,
+            0.0,
+            -0.25,
+        ] {
+            let text = format_percent(p);
+            assert_eq!(parse_percent(&text), Ok(p), "{} as {}", p, text);
+        }
+    }
+
+    #[test]
+    fn percentages_are_written_without_float_noise() {
+        // 100 * 0.07 is 7.000000000000001.
+        assert_eq!(format_percent(0.07), "7");
+        assert_eq!(format_percent(0.125), "12.5");
+        assert_eq!(format_percent(1.0), "100");
+        assert_eq!(format_percent(0.001), "0.1");
+    }
+
+    #[test]
+    fn reads_other_forms() {
+        assert_eq!(parse_percent(" 50 "), Ok(0.5));
+        assert_eq!(parse_percent("1e1"), Ok(0.1));
+        assert!(parse_percent("half").is_err());
+        assert!(parse_percent("").is_err());
+    }
+}
@@ -0,0 +1,179 @@
+/// Where the best action would change as one hypothesis's posterior moves,
+/// with the other hypotheses keeping their relative odds.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Threshold {
+    pub hypothesis: usize,
+    /// The posterior, in percent, below which another action is best, and it.
+    pub below: Option<(f64, usize)>,
+    /// The posterior, in percent, above which another action is best, and it.
+    pub above: Option<(f64, usize)>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Decision {
+    /// Expected utility of each action under the posterior.
+    pub expected: Vec<f64>,
+    pub best: usize,
+    /// One per hypothesis.
+    pub thresholds: Vec<Threshold>,
+}
+
+fn best_action(expected: &[f64]) -> usize {
+    expected.iter().enumerate().fold(
+        0,
+        |best, (idx, u)| if *u > expected[best] { idx } else { best },
+    )
+}
+
+/// Expected utility of every action as the posterior of `hyp_idx` moves to
+/// `t` (a probability), as `(utility at t = 1, utility at t = 0)` pairs, so
+/// each action's utility is the line `t * a + (1 - t) * b`.
+fn lines(weights: &[f64], utilities: &[Vec<f64>], hyp_idx: usize) -> Vec<(f64, f64)> {
+    let rest: f64 = weights
+        .iter()
+        .enumerate()
+        .filter(|(idx, _)| *idx != hyp_idx)
+        .map(|(_, w)| w)
+        .sum();
+    let others = (weights.len() - 1).max(1) as f64;
+    utilities
+        .iter()
+        .map(|row| {
+            let at_zero = row
+                .iter()
+                .enumerate()
+                .filter(|(idx, _)| *idx != hyp_idx)
+                .map(|(idx, u)| {
+                    // With nothing left for the others, weigh them equally.
+                    if rest > 0.0 {
+                        u * weights[idx] / rest
+                    } else {
+                        u / others
+                    }
+                })
+                .sum();
+            (row[hyp_idx], at_zero)
+        })
+        .collect()
+}
+
+fn threshold(weights: &[f64], utilities: &[Vec<f64>], hyp_idx: usize, best: usize) -> Threshold {
+    let lines = lines(weights, utilities, hyp_idx);
+    let t0 = weights[hyp_idx];
+    let (best_one, best_zero) = lines[best];
+    let mut below: Option<(f64, usize)> = None;
+    let mut above: Option<(f64, usize)> = None;
+    for (action_idx, (one, zero)) in lines.iter().enumerate() {
+        if action_idx == best {
+            continue;
+        }
+        let slope = (best_one - best_zero) - (one - zero);
+        if slope == 0.0 {
+            continue;
+        }
+        let t = (zero - best_zero) / slope;
+        if !(0.0..=1.0).contains(&t) {
+            continue;
+        }
+        // Keep the crossing nearest the current posterior on each side.
+        if t < t0 && !matches!(below, Some((b, _)) if b >= t) {
+            below = Some((t, action_idx));
+        } else if t > t0 && !matches!(above, Some((a, _)) if a <= t) {
+            above = Some((t, action_idx));
+        }
+    }
+    Threshold {
+        hypothesis: hyp_idx,
+        below: below.map(|(t, action_idx)| (100.0 * t, action_idx)),
+        above: above.map(|(t, action_idx)| (100.0 * t, action_idx)),
+    }
+}
+
+/// Expected utilities and the best action under `posterior` (any scale),
+/// or `None` if there are no actions or the posterior is all zeros.
+pub fn decide(posterior: &[f64], utilities: &[Vec<f64>]) -> Option<Decision> {
+    let total: f64 = posterior.iter().sum();
+    if utilities.is_empty() || total <= 0.0 {
+        return None;
+    }
+    let weights: Vec<f64> = posterior.iter().map(|p| p / total).collect();
+    let expected: Vec<f64> = utilities
+        .iter()
+        .map(|row| row.iter().zip(&weights).map(|(u, w)| u * w).sum())
+        .collect();
+    let best = best_action(&expected);
+    let thresholds = (0..weights.len())
+        .map(|hyp_idx| threshold(&weights, utilities, hyp_idx, best))
+        .collect();
+    Some(Decision {
+        expected,
+        best,
+        thresholds,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn close(found: Option<(f64, usize)>, expected: (f64, usize)) -> bool {
+        found.is_some_and(|(t, action_idx)| {
+            (t - expected.0).abs() < 1e-9 && action_idx == expected.1
+        })
+    }
+
+    #[test]
+    fn thresholds_are_where_the_best_action_changes() {
+        // Umbrella or not: 0.8 against 0.7 at a 30% chance of rain.
+        let decision = decide(&[30.0, 70.0], &[vec![5.0, -1.0], vec![0.0, 1.0]]).unwrap();
+        assert_eq!(decision.best, 0);
+        assert!((decision.expected[0] - 0.8).abs() < 1e-9);
+        assert!(close(decision.thresholds[0].below, (200.0 / 7.0, 1)));
+        assert_eq!(decision.thresholds[0].above, None);
+        assert_eq!(decision.thresholds[1].below, None);
+        assert!(close(decision.thresholds[1].above, (500.0 / 7.0, 1)));
+    }
+
+    #[test]
+    fn the_nearest_crossing_with_the_best_action_is_kept() {
+        // C is best at 50%; D overtakes it at 4/7 before A does at 60%.
+        let utilities = [
+            vec![10.0, 0.0],
+            vec![0.0, 10.0],
+            vec![6.0, 6.0],
+            vec![9.0, 2.0],
+        ];
+        let decision = decide(&[1.0, 1.0], &utilities).unwrap();
+        assert_eq!(decision.best, 2);
+        assert!(close(decision.thresholds[0].above, (400.0 / 7.0, 3)));
+        assert!(close(decision.thresholds[0].below, (40.0, 1)));
+    }
+
+    #[test]
+    fn ties_go_to_the_first_action() {
+        let decision = decide(&[1.0, 1.0], &[vec![10.0, 0.0], vec![0.0, 10.0]]).unwrap();
+        assert_eq!(decision.best, 0);
+        // The lines cross at the current posterior, which isn't a change.
+        assert_eq!(decision.thresholds[0].below, None);
+        assert_eq!(decision.thresholds[0].above, None);
+        // Identical actions never cross.
+        let same = decide(&[1.0, 3.0], &[vec![1.0, 2.0], vec![1.0, 2.0]]).unwrap();
+        assert_eq!(same.best, 0);
+        assert!(same
+            .thresholds
+            .iter()
+            .all(|t| t.below.is_none() && t.above.is_none()));
+    }
+
+    #[test]
+    fn a_dominant_action_has_no_thresholds() {
+        let decision = decide(&[1.0, 1.0], &[vec![0.0, 0.0], vec![5.0, 5.0]]).unwrap();
+        assert_eq!(decision.best, 1);
+        assert!(decision
+            .thresholds
+            .iter()
+            .all(|t| t.below.is_none() && t.above.is_none()));
+        assert_eq!(decide(&[0.0, 0.0], &[vec![1.0, 0.0]]), None);
+        assert_eq!(decide(&[1.0, 1.0], &[]), None);
+    }
+}
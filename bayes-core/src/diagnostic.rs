@@ -0,0 +1,240 @@
+use crate::model::BayesData;
+use crate::validate::Invalid;
+use std::fmt;
+
+/// Whether a problem stopped the file loading.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    Error,
+    /// Something odd that didn't stop the file loading.
+    Warning,
+}
+
+/// One problem found reading an imported file: where it is, what's wrong and
+/// how to fix it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    /// 1-based, or 0 when the problem is with the file as a whole or is
+    /// placed by a field instead.
+    pub line: usize,
+    /// 1-based and counted in characters, or 0 along with `line`.
+    pub column: usize,
+    /// The text at fault, or the field at fault when `line` is 0; empty when
+    /// something is missing.
+    pub text: String,
+    pub message: String,
+    pub fix: String,
+    pub severity: Severity,
+}
+
+impl Diagnostic {
+    /// A problem with `text`, which starts `start` bytes into `line`, which
+    /// is line `line_no` of the file. `line` may run on over line breaks, as
+    /// a CSV row can, and `text` is then placed on the line it starts on.
+    pub(crate) fn at(
+        line_no: usize,
+        line: &str,
+        start: usize,
+        text: &str,
+        message: impl Into<String>,
+        fix: impl Into<String>,
+    ) -> Diagnostic {
+        debug_assert!(line.get(start..start + text.len()) == Some(text));
+        let before = line.get(..start).unwrap_or(line);
+        let (line_no, before) = match before.rfind('\n') {
+            Some(idx) => (line_no + before.matches('\n').count(), &before[idx + 1..]),
+            None => (line_no, before),
+        };
+        Diagnostic {
+            line: line_no,
+            column: before.chars().count() + 1,
+            text: text.to_string(),
+            message: message.into(),
+            fix: fix.into(),
+            severity: Severity::Error,
+        }
+    }
+
+    /// The same problem, as one that doesn't stop the file loading.
+    pub(crate) fn warning(self) -> Diagnostic {
+        Diagnostic {
+            severity: Severity::Warning,
+            ..self
+        }
+    }
+
+    /// A problem with a field of a structured file, such as
+    /// `likelihoods[1][0]` in JSON, rather than a line.
+    pub(crate) fn in_field(
+        path: String,
+        message: impl Into<String>,
+        fix: impl Into<String>,
+    ) -> Diagnostic {
+        Diagnostic {
+            text: path,
+            ..Diagnostic::whole_file(message, fix)
+        }
+    }
+
+    /// A problem with the file as a whole, such as a missing section.
+    pub(crate) fn whole_file(message: impl Into<String>, fix: impl Into<String>) -> Diagnostic {
+        Diagnostic {
+            line: 0,
+            column: 0,
+            text: String::new(),
+            message: message.into(),
+            fix: fix.into(),
+            severity: Severity::Error,
+        }
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.severity == Severity::Warning {
+            write!(f, "Warning: ")?;
+        }
+        if self.line > 0 {
+            write!(f, "Line {}, column {}: ", self.line, self.column)?;
+        }
+        write!(f, "{}", self.message)?;
+        if !self.text.is_empty() {
+            write!(f, " (`{}`)", self.text)?;
+        }
+        write!(f, ". {}", self.fix)
+    }
+}
+
+/// Some text in a file, the line it's on and where in the line it starts,
+/// for pointing errors at it. Split it with its own methods, which keep
+/// track of where each part starts.
+#[derive(Clone, Copy)]
+pub(crate) struct Located<'a> {
+    pub line_no: usize,
+    /// The line, or lines for a CSV row with a line break in a cell.
+    pub line: &'a str,
+    /// In bytes from the start of `line`.
+    pub start: usize,
+    pub text: &'a str,
+}
+
+impl<'a> Located<'a> {
+    /// The whole of line `line_no`.
+    pub fn line(line_no: usize, line: &'a str) -> Located<'a> {
+        Located {
+            line_no,
+            line,
+            start: 0,
+            text: line,
+        }
+    }
+
+    /// The text between byte offsets `start` and `end` of this text.
+    pub fn slice(&self, start: usize, end: usize) -> Located<'a> {
+        Located {
+            start: self.start + start,
+            text: &self.text[start..end],
+            ..*self
+        }
+    }
+
+    pub fn trim(&self) -> Located<'a> {
+        self.trim_start().trim_end()
+    }
+
+    pub fn trim_start(&self) -> Located<'a> {
+        let trimmed = self.text.trim_start();
+        self.slice(self.text.len() - trimmed.len(), self.text.len())
+    }
+
+    pub fn trim_end(&self) -> Located<'a> {
+        self.slice(0, self.text.trim_end().len())
+    }
+
+    /// The text before and after the `len` bytes at `idx`, e.g. a delimiter.
+    pub fn split_around(&self, idx: usize, len: usize) -> (Located<'a>, Located<'a>) {
+        (self.slice(0, idx), self.slice(idx + len, self.text.len()))
+    }
+
+    pub fn split_once(&self, delimiter: char) -> Option<(Located<'a>, Located<'a>)> {
+        let idx = self.text.find(delimiter)?;
+        Some(self.split_around(idx, delimiter.len_utf8()))
+    }
+
+    pub fn rsplit_once(&self, delimiter: char) -> Option<(Located<'a>, Located<'a>)> {
+        let idx = self.text.rfind(delimiter)?;
+        Some(self.split_around(idx, delimiter.len_utf8()))
+    }
+
+    pub fn split(&self, delimiter: char) -> Vec<Located<'a>> {
+        let mut parts = Vec::new();
+        let mut rest = *self;
+        while let Some((part, after)) = rest.split_once(delimiter) {
+            parts.push(part);
+            rest = after;
+        }
+        parts.push(rest);
+        parts
+    }
+
+    pub fn strip_prefix(&self, prefix: char) -> Option<Located<'a>> {
+        let rest = self.text.strip_prefix(prefix)?;
+        Some(self.slice(self.text.len() - rest.len(), self.text.len()))
+    }
+
+    pub fn strip_suffix(&self, suffix: char) -> Option<Located<'a>> {
+        let rest = self.text.strip_suffix(suffix)?;
+        Some(self.slice(0, rest.len()))
+    }
+
+    pub fn error(&self, message: impl Into<String>, fix: impl Into<String>) -> Diagnostic {
+        Diagnostic::at(self.line_no, self.line, self.start, self.text, message, fix)
+    }
+
+    pub fn invalid(&self, invalid: Invalid) -> Diagnostic {
+        self.error(invalid.message, invalid.fix)
+    }
+}
+
+/// A file that was read, and any warnings about it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Imported {
+    pub data: BayesData,
+    pub warnings: Vec<Diagnostic>,
+}
+
+/// Why a file couldn't be imported, as every problem found in one pass.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ImportError {
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+impl From<Diagnostic> for ImportError {
+    fn from(diagnostic: Diagnostic) -> ImportError {
+        ImportError {
+            diagnostics: vec![diagnostic],
+        }
+    }
+}
+
+impl std::error::Error for ImportError {}
+
+#[cfg(test)]
+impl ImportError {
+    /// The line, column and text of each problem, for tests.
+    pub(crate) fn positions(&self) -> Vec<(usize, usize, String)> {
+        self.diagnostics
+            .iter()
+            .map(|diagnostic| (diagnostic.line, diagnostic.column, diagnostic.text.clone()))
+            .collect()
+    }
+}
+
+impl fmt::Display for ImportError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for diagnostic in &self.diagnostics {
+            writeln!(f, "{}", diagnostic)?;
+        }
+        Ok(())
+    }
+}
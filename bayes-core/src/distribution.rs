@@ -0,0 +1,242 @@
+use crate::validate::check_distribution;
+use serde::{Deserialize, Serialize};
+use std::f64::consts::PI;
+use std::fmt;
+
+/// A likelihood function for a measured value under one hypothesis.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub enum Distribution {
+    Normal { mean: f64, sd: f64 },
+    LogNormal { mu: f64, sigma: f64 },
+    Exponential { rate: f64 },
+    Uniform { low: f64, high: f64 },
+}
+
+impl Default for Distribution {
+    fn default() -> Self {
+        Distribution::Normal { mean: 0.0, sd: 1.0 }
+    }
+}
+
+impl Distribution {
+    pub const NAMES: [&'static str; 4] = ["Normal", "LogNormal", "Exponential", "Uniform"];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Distribution::Normal { .. } => "Normal",
+            Distribution::LogNormal { .. } => "LogNormal",
+            Distribution::Exponential { .. } => "Exponential",
+            Distribution::Uniform { .. } => "Uniform",
+        }
+    }
+
+    pub fn param_names(&self) -> &'static [&'static str] {
+        match self {
+            Distribution::Normal { .. } => &["mean", "sd"],
+            Distribution::LogNormal { .. } => &["μ", "σ"],
+            Distribution::Exponential { .. } => &["rate"],
+            Distribution::Uniform { .. } => &["low", "high"],
+        }
+    }
+
+    pub fn params(&self) -> Vec<f64> {
+        match *self {
+            Distribution::Normal { mean, sd } => vec![mean, sd],
+            Distribution::LogNormal { mu, sigma } => vec![mu, sigma],
+            Distribution::Exponential { rate } => vec![rate],
+            Distribution::Uniform { low, high } => vec![low, high],
+        }
+    }
+
+    /// Builds a distribution from its name and parameters, in the order of
+    /// `param_names`. Missing parameters take the defaults of `with_name`.
+    pub fn from_params(name: &str, params: &[f64]) -> Result<Self, String> {
+        let mut dist = Distribution::with_name(name)?;
+        if params.len() > dist.params().len() {
+            return Err(format!(
+                "{} takes {} parameters",
+                dist.name(),
+                dist.params().len()
+            ));
+        }
+        for (idx, value) in params.iter().enumerate() {
+            dist = dist.with_param(idx, *value);
+        }
+        dist.validate()?;
+        Ok(dist)
+    }
+
+    /// A copy with the parameter at `idx` (in `param_names` order) replaced.
+    pub fn with_param(&self, idx: usize, value: f64) -> Self {
+        let mut params = self.params();
+        params[idx] = value;
+        match self {
+            Distribution::Normal { .. } => Distribution::Normal {
+                mean: params[0],
+                sd: params[1],
+            },
+            Distribution::LogNormal { .. } => Distribution::LogNormal {
+                mu: params[0],
+                sigma: params[1],
+            },
+            Distribution::Exponential { .. } => Distribution::Exponential { rate: params[0] },
+            Distribution::Uniform { .. } => Distribution::Uniform {
+                low: params[0],
+                high: params[1],
+            },
+        }
+    }
+
+    /// The standard form of the named distribution.
+    pub fn with_name(name: &str) -> Result<Self, String> {
+        match name.to_lowercase().replace(['-', ' '], "").as_str() {
+            "normal" => Ok(Distribution::Normal { mean: 0.0, sd: 1.0 }),
+            "lognormal" => Ok(Distribution::LogNormal {
+                mu: 0.0,
+                sigma: 1.0,
+            }),
+            "exponential" => Ok(Distribution::Exponential { rate: 1.0 }),
+            "uniform" => Ok(Distribution::Uniform {
+                low: 0.0,
+                high: 1.0,
+            }),
+            _ => Err(format!(
+                "Unknown distribution '{}', expected one of {}",
+                name,
+                Distribution::NAMES.join(", ")
+            )),
+        }
+    }
+
+    /// Whether the parameters make a distribution, see `check_distribution`.
+    pub fn validate(&self) -> Result<(), String> {
+        check_distribution(self).map_err(|invalid| invalid.message)
+    }
+
+    /// The probability density at `x`; 0 for invalid parameters.
+    pub fn density(&self, x: f64) -> f64 {
+        self.log_density(x).exp()
+    }
+
+    /// The log of `density`, which stays finite far into the tails where the
+    /// density itself underflows to 0.
+    pub fn log_density(&self, x: f64) -> f64 {
+        if self.validate().is_err() {
+            return f64::NEG_INFINITY;
+        }
+        match *self {
+            Distribution::Normal { mean, sd } => {
+                let z = (x - mean) / sd;
+                -0.5 * z * z - (sd * (2.0 * PI).sqrt()).ln()
+            }
+            Distribution::LogNormal { mu, sigma } => {
+                if x <= 0.0 {
+                    return f64::NEG_INFINITY;
+                }
+                let z = (x.ln() - mu) / sigma;
+                -0.5 * z * z - (x * sigma * (2.0 * PI).sqrt()).ln()
+            }
+            Distribution::Exponential { rate } => {
+                if x < 0.0 {
+                    f64::NEG_INFINITY
+                } else {
+                    rate.ln() - rate * x
+                }
+            }
+            Distribution::Uniform { low, high } => {
+                if (low..=high).contains(&x) {
+                    -(high - low).ln()
+                } else {
+                    f64::NEG_INFINITY
+                }
+            }
+        }
+    }
+
+    /// Where almost all of the probability mass lies, for plotting.
+    pub fn range(&self) -> (f64, f64) {
+        match *self {
+            Distribution::Normal { mean, sd } => (mean - 4.0 * sd, mean + 4.0 * sd),
+            Distribution::LogNormal { mu, sigma } => (0.0, (mu + 3.0 * sigma).exp()),
+            Distribution::Exponential { rate } => (0.0, 5.0 / rate),
+            Distribution::Uniform { low, high } => {
+                let margin = 0.1 * (high - low);
+                (low - margin, high + margin)
+            }
+        }
+    }
+
+    /// Parses `Normal(5, 1.2)` and the like.
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let text = text.trim();
+        let (name, params) = text
+            .strip_suffix(')')
+            .and_then(|rest| rest.split_once('('))
+            .ok_or_else(|| format!("Expected a distribution like Normal(0, 1), got '{}'", text))?;
+        let params = params
+            .split(',')
+            .map(str::trim)
+            .filter(|param| !param.is_empty())
+            .map(|param| {
+                param
+                    .parse::<f64>()
+                    .map_err(|_| format!("'{}' is not a number", param))
+            })
+            .collect::<Result<Vec<f64>, String>>()?;
+        Distribution::from_params(name.trim(), &params)
+    }
+}
+
+impl fmt::Display for Distribution {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let params: Vec<String> = self.params().iter().map(f64::to_string).collect();
+        write!(f, "{}({})", self.name(), params.join(", "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn log_density_matches_density() {
+        let dists = [
+            Distribution::Normal { mean: 1.0, sd: 2.0 },
+            Distribution::LogNormal {
+                mu: 0.5,
+                sigma: 0.8,
+            },
+            Distribution::Exponential { rate: 1.5 },
+            Distribution::Uniform {
+                low: -1.0,
+                high: 3.0,
+            },
+        ];
+        for dist in dists {
+            for x in [-0.5, 0.25, 1.0, 2.5] {
+                let density = dist.density(x);
+                let expected = if density > 0.0 {
+                    density.ln()
+                } else {
+                    f64::NEG_INFINITY
+                };
+                let log_density = dist.log_density(x);
+                assert!(
+                    log_density == expected || (log_density - expected).abs() < 1e-12,
+                    "{} at {}",
+                    dist,
+                    x
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn log_density_is_finite_where_density_underflows() {
+        let dist = Distribution::Normal { mean: 0.0, sd: 1.0 };
+        assert_eq!(dist.density(100.0), 0.0);
+        assert!((dist.log_density(100.0) + 5000.0 + (2.0 * PI).sqrt().ln()).abs() < 1e-9);
+        let invalid = Distribution::Normal { mean: 0.0, sd: 0.0 };
+        assert_eq!(invalid.log_density(0.0), f64::NEG_INFINITY);
+    }
+}
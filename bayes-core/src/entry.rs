@@ -0,0 +1,182 @@
+use crate::model::BayesData;
+use serde::{Deserialize, Serialize};
+
+/// How the likelihoods of a yes/no evidence row are typed in. Whatever the
+/// mode, `BayesData::likelihoods` keeps the probabilities.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Default)]
+pub enum EntryMode {
+    /// P(evidence | hypothesis) in percent.
+    #[default]
+    Percent,
+    /// How many times more likely the evidence is than under `reference`.
+    Ratio { reference: usize },
+    /// The same ratio in decibels, `10 log10(ratio)`.
+    Decibels { reference: usize },
+}
+
+impl EntryMode {
+    /// The hypothesis ratios are taken against, if any.
+    pub fn reference(&self) -> Option<usize> {
+        match self {
+            EntryMode::Percent => None,
+            EntryMode::Ratio { reference } | EntryMode::Decibels { reference } => Some(*reference),
+        }
+    }
+
+    /// The same kind of mode against another reference.
+    pub fn with_reference(&self, reference: usize) -> EntryMode {
+        match self {
+            EntryMode::Percent => EntryMode::Percent,
+            EntryMode::Ratio { .. } => EntryMode::Ratio { reference },
+            EntryMode::Decibels { .. } => EntryMode::Decibels { reference },
+        }
+    }
+}
+
+pub fn ratio_to_db(ratio: f64) -> f64 {
+    10.0 * ratio.log10()
+}
+
+pub fn db_to_ratio(db: f64) -> f64 {
+    10f64.powf(db / 10.0)
+}
+
+/// Each likelihood in `row` divided by the one at `reference`. Anything over a
+/// 0 reference is infinite, and 0 over 0 is NaN.
+pub fn likelihood_ratios(row: &[f64], reference: usize) -> Vec<f64> {
+    let base = row.get(reference).copied().unwrap_or(0.0);
+    row.iter().map(|l| l / base).collect()
+}
+
+/// `row` with the likelihood at `hyp_idx` set to `ratio` times the one at
+/// `reference`. If that would go over 1, the whole row is scaled down so it
+/// doesn't, which keeps every ratio between hypotheses. A reference of 0 has
+/// no ratios to keep, so it's taken as 1 first.
+pub fn with_likelihood_ratio(
+    row: &[f64],
+    reference: usize,
+    hyp_idx: usize,
+    ratio: f64,
+) -> Vec<f64> {
+    let mut row = row.to_vec();
+    if hyp_idx == reference || !ratio.is_finite() || ratio < 0.0 {
+        return row;
+    }
+    if row[reference] <= 0.0 {
+        row[reference] = 1.0;
+    }
+    row[hyp_idx] = row[reference] * ratio;
+    let max = row.iter().cloned().fold(0.0, f64::max);
+    if max > 1.0 {
+        for l in row.iter_mut() {
+            *l /= max;
+        }
+    }
+    row
+}
+
+impl BayesData {
+    pub fn entry_mode(&self, ev_idx: usize) -> EntryMode {
+        self.entry_modes.get(ev_idx).copied().unwrap_or_default()
+    }
+
+    pub fn set_entry_mode(&mut self, ev_idx: usize, mode: EntryMode) {
+        if self.entry_modes.len() < self.evidence.len() {
+            self.entry_modes
+                .resize(self.evidence.len(), EntryMode::default());
+        }
+        self.entry_modes[ev_idx] = mode;
+    }
+
+    /// Sets the likelihood at `hyp_idx` from a ratio against the row's
+    /// reference hypothesis. Does nothing in percent mode.
+    pub fn set_likelihood_ratio(&mut self, ev_idx: usize, hyp_idx: usize, ratio: f64) {
+        if let Some(reference) = self.entry_mode(ev_idx).reference() {
+            self.likelihoods[ev_idx] =
+                with_likelihood_ratio(&self.likelihoods[ev_idx], reference, hyp_idx, ratio);
+        }
+    }
+
+    /// Keeps every reference pointing at the same hypothesis. Rows whose
+    /// reference was removed fall back to the first hypothesis.
+    pub(crate) fn remove_hypothesis_from_entry_modes(&mut self, hyp_idx: usize) {
+        for mode in self.entry_modes.iter_mut() {
+            if let Some(reference) = mode.reference() {
+                *mode = mode.with_reference(if reference > hyp_idx {
+                    reference - 1
+                } else if reference == hyp_idx {
+                    0
+                } else {
+                    reference
+                });
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decibels_are_ten_log10_of_the_ratio() {
+        assert_eq!(ratio_to_db(10.0), 10.0);
+        assert_eq!(ratio_to_db(1.0), 0.0);
+        assert!((ratio_to_db(2.0) - 3.0103).abs() < 1e-4);
+        assert!((db_to_ratio(-10.0) - 0.1).abs() < 1e-12);
+        assert!((db_to_ratio(ratio_to_db(7.0)) - 7.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn ratios_are_against_the_reference() {
+        assert_eq!(likelihood_ratios(&[0.2, 0.4, 0.1], 0), vec![1.0, 2.0, 0.5]);
+        let over_zero = likelihood_ratios(&[0.0, 0.4, 0.0], 0);
+        assert_eq!(over_zero[1], f64::INFINITY);
+        assert!(over_zero[2].is_nan());
+    }
+
+    #[test]
+    fn entering_a_ratio_keeps_the_row_a_probability() {
+        assert_eq!(
+            with_likelihood_ratio(&[0.2, 0.3], 0, 1, 2.0),
+            vec![0.2, 0.4]
+        );
+        // 0.5 * 4 would be 2, so the row is halved and the ratio kept.
+        assert_eq!(
+            with_likelihood_ratio(&[0.5, 0.3], 0, 1, 4.0),
+            vec![0.25, 1.0]
+        );
+        assert_eq!(
+            with_likelihood_ratio(&[0.0, 0.3], 0, 1, 0.5),
+            vec![1.0, 0.5]
+        );
+        // The reference itself, and ratios that aren't ones, change nothing.
+        assert_eq!(
+            with_likelihood_ratio(&[0.2, 0.3], 0, 0, 3.0),
+            vec![0.2, 0.3]
+        );
+        assert_eq!(
+            with_likelihood_ratio(&[0.2, 0.3], 0, 1, -1.0),
+            vec![0.2, 0.3]
+        );
+    }
+
+    #[test]
+    fn entry_modes_follow_their_reference_hypothesis() {
+        let mut data = BayesData {
+            hypotheses: vec!["A".to_string(), "B".to_string(), "C".to_string()],
+            evidence: vec!["E".to_string(), "F".to_string()],
+            likelihoods: vec![vec![0.5, 0.5, 0.5]; 2],
+            ..BayesData::default()
+        };
+        data.set_likelihood_ratio(0, 1, 0.5);
+        assert_eq!(data.likelihoods[0], vec![0.5, 0.5, 0.5]);
+        data.set_entry_mode(0, EntryMode::Decibels { reference: 2 });
+        data.set_likelihood_ratio(0, 1, 0.5);
+        assert_eq!(data.likelihoods[0], vec![0.5, 0.25, 0.5]);
+        data.set_entry_mode(1, EntryMode::Ratio { reference: 1 });
+        data.remove_hypothesis_from_entry_modes(1);
+        assert_eq!(data.entry_mode(0), EntryMode::Decibels { reference: 1 });
+        assert_eq!(data.entry_mode(1), EntryMode::Ratio { reference: 0 });
+    }
+}
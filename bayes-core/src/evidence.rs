@@ -0,0 +1,261 @@
+use crate::calc::exp_odds;
+use crate::distribution::Distribution;
+use crate::model::BayesData;
+use serde::{Deserialize, Serialize};
+
+/// What kind of observation an evidence row is. Whatever the kind, the row in
+/// `BayesData::likelihoods` holds the likelihood of what was actually observed,
+/// so `recalculate` doesn't need to know about kinds.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+pub enum EvidenceKind {
+    /// A yes/no observation with likelihood `p` (and `1 - p` for "no").
+    #[default]
+    Binary,
+    Categorical(Categorical),
+    Continuous(Continuous),
+}
+
+static BINARY: EvidenceKind = EvidenceKind::Binary;
+
+/// An observation with several possible outcomes.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Categorical {
+    pub outcomes: Vec<String>,
+    /// `table[hyp_idx][outcome_idx]`; each hypothesis's row sums to 1.
+    pub table: Vec<Vec<f64>>,
+    pub observed: usize,
+}
+
+impl Categorical {
+    /// A two-outcome version of a binary row, observed as "yes".
+    pub fn from_binary(likelihoods: &[f64]) -> Self {
+        Categorical {
+            outcomes: vec!["Observed".to_string(), "Not observed".to_string()],
+            table: likelihoods.iter().map(|p| vec![*p, 1.0 - p]).collect(),
+            observed: 0,
+        }
+    }
+
+    pub fn observed_likelihoods(&self) -> Vec<f64> {
+        self.table.iter().map(|row| row[self.observed]).collect()
+    }
+
+    /// Sets one cell and rescales the rest of the row so it still sums to 1.
+    pub fn set_likelihood(&mut self, hyp_idx: usize, outcome_idx: usize, p: f64) {
+        let p = p.clamp(0.0, 1.0);
+        let row = &mut self.table[hyp_idx];
+        let others: f64 = row
+            .iter()
+            .enumerate()
+            .filter(|(idx, _)| *idx != outcome_idx)
+            .map(|(_, x)| x)
+            .sum();
+        let num_others = (row.len() - 1) as f64;
+        for (idx, x) in row.iter_mut().enumerate() {
+            if idx == outcome_idx {
+                *x = p;
+            } else if others > 0.0 {
+                *x *= (1.0 - p) / others;
+            } else {
+                *x = (1.0 - p) / num_others;
+            }
+        }
+    }
+
+    /// New outcomes start at 0%, so every row still sums to 1.
+    pub fn add_outcome(&mut self, name: String) {
+        self.outcomes.push(name);
+        for row in self.table.iter_mut() {
+            row.push(0.0);
+        }
+    }
+
+    pub fn remove_outcome(&mut self, outcome_idx: usize) {
+        if self.outcomes.len() <= 2 {
+            return;
+        }
+        self.outcomes.remove(outcome_idx);
+        for row in self.table.iter_mut() {
+            row.remove(outcome_idx);
+            let total: f64 = row.iter().sum();
+            let len = row.len() as f64;
+            for x in row.iter_mut() {
+                *x = if total > 0.0 { *x / total } else { 1.0 / len };
+            }
+        }
+        if self.observed >= outcome_idx && self.observed > 0 {
+            self.observed -= 1;
+        }
+    }
+
+    fn add_hypothesis(&mut self) {
+        let len = self.outcomes.len();
+        self.table.push(vec![1.0 / len as f64; len]);
+    }
+}
+
+/// A measured value, with a likelihood function for it under each hypothesis.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Continuous {
+    /// One per hypothesis.
+    pub distributions: Vec<Distribution>,
+    pub observed: f64,
+}
+
+impl Continuous {
+    pub fn new(num_hypotheses: usize) -> Self {
+        Continuous {
+            distributions: vec![Distribution::default(); num_hypotheses],
+            observed: 0.0,
+        }
+    }
+
+    /// The densities at the observed value, scaled so the largest is 1. Only
+    /// their ratios matter to the posterior, and this keeps them in [0, 1]
+    /// like every other likelihood. They're scaled in log space, so an
+    /// observation far out in every tail still tells the hypotheses apart.
+    pub fn observed_likelihoods(&self) -> Vec<f64> {
+        exp_odds(
+            self.distributions
+                .iter()
+                .map(|dist| dist.log_density(self.observed))
+                .collect(),
+        )
+    }
+
+    /// An x range that covers every distribution and the observation.
+    pub fn range(&self) -> (f64, f64) {
+        let (low, high) = self
+            .distributions
+            .iter()
+            .filter(|dist| dist.validate().is_ok())
+            .map(Distribution::range)
+            .fold((self.observed, self.observed), |(low, high), (a, b)| {
+                (low.min(a), high.max(b))
+            });
+        if high > low {
+            (low, high)
+        } else {
+            (low - 1.0, high + 1.0)
+        }
+    }
+}
+
+impl BayesData {
+    pub fn kind(&self, ev_idx: usize) -> &EvidenceKind {
+        self.kinds.get(ev_idx).unwrap_or(&BINARY)
+    }
+
+    pub fn categorical(&self, ev_idx: usize) -> Option<&Categorical> {
+        match self.kind(ev_idx) {
+            EvidenceKind::Categorical(categorical) => Some(categorical),
+            _ => None,
+        }
+    }
+
+    pub fn set_kind(&mut self, ev_idx: usize, kind: EvidenceKind) {
+        if self.kinds.len() < self.evidence.len() {
+            self.kinds.resize(self.evidence.len(), EvidenceKind::Binary);
+        }
+        self.kinds[ev_idx] = kind;
+        self.sync_likelihoods(ev_idx);
+    }
+
+    pub fn continuous(&self, ev_idx: usize) -> Option<&Continuous> {
+        match self.kind(ev_idx) {
+            EvidenceKind::Continuous(continuous) => Some(continuous),
+            _ => None,
+        }
+    }
+
+    /// Applies `edit` to a continuous row and refreshes its likelihoods.
+    pub fn edit_continuous(&mut self, ev_idx: usize, edit: impl FnOnce(&mut Continuous)) {
+        if let Some(EvidenceKind::Continuous(continuous)) = self.kinds.get_mut(ev_idx) {
+            edit(continuous);
+            self.sync_likelihoods(ev_idx);
+        }
+    }
+
+    /// Applies `edit` to a categorical row and refreshes its likelihoods.
+    pub fn edit_categorical(&mut self, ev_idx: usize, edit: impl FnOnce(&mut Categorical)) {
+        if let Some(EvidenceKind::Categorical(categorical)) = self.kinds.get_mut(ev_idx) {
+            edit(categorical);
+            self.sync_likelihoods(ev_idx);
+        }
+    }
+
+    /// Copies the likelihoods of what was observed into `likelihoods`.
+    pub(crate) fn sync_likelihoods(&mut self, ev_idx: usize) {
+        match self.kind(ev_idx) {
+            EvidenceKind::Binary => {}
+            EvidenceKind::Categorical(categorical) => {
+                self.likelihoods[ev_idx] = categorical.observed_likelihoods();
+            }
+            EvidenceKind::Continuous(continuous) => {
+                self.likelihoods[ev_idx] = continuous.observed_likelihoods();
+            }
+        }
+    }
+
+    pub(crate) fn add_hypothesis_to_kinds(&mut self) {
+        for kind in self.kinds.iter_mut() {
+            match kind {
+                EvidenceKind::Binary => {}
+                EvidenceKind::Categorical(categorical) => categorical.add_hypothesis(),
+                EvidenceKind::Continuous(continuous) => {
+                    continuous.distributions.push(Distribution::default())
+                }
+            }
+        }
+        for ev_idx in 0..self.kinds.len() {
+            self.sync_likelihoods(ev_idx);
+        }
+    }
+
+    pub(crate) fn remove_hypothesis_from_kinds(&mut self, hyp_idx: usize) {
+        for kind in self.kinds.iter_mut() {
+            match kind {
+                EvidenceKind::Binary => {}
+                EvidenceKind::Categorical(categorical) => {
+                    categorical.table.remove(hyp_idx);
+                }
+                EvidenceKind::Continuous(continuous) => {
+                    continuous.distributions.remove(hyp_idx);
+                }
+            }
+        }
+        // Continuous rows are scaled to their largest density, which may be gone.
+        for ev_idx in 0..self.kinds.len() {
+            self.sync_likelihoods(ev_idx);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn continuous_likelihoods_survive_far_tails() {
+        let continuous = Continuous {
+            distributions: vec![
+                Distribution::Normal { mean: 0.0, sd: 1.0 },
+                Distribution::Normal { mean: 1.0, sd: 1.0 },
+            ],
+            observed: 100.0,
+        };
+        let likelihoods = continuous.observed_likelihoods();
+        assert_eq!(likelihoods[1], 1.0);
+        // exp(-99.5), rather than both underflowing to 0.
+        assert!((likelihoods[0].ln() + 99.5).abs() < 1e-9);
+    }
+
+    #[test]
+    fn continuous_likelihoods_are_0_when_nothing_fits() {
+        let continuous = Continuous {
+            distributions: vec![Distribution::Exponential { rate: 1.0 }; 2],
+            observed: -1.0,
+        };
+        assert_eq!(continuous.observed_likelihoods(), vec![0.0, 0.0]);
+    }
+}
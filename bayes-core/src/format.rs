@@ -0,0 +1,103 @@
+use crate::csv::{parse_csv, to_csv};
+use crate::diagnostic::{ImportError, Imported};
+use crate::json::{parse_json, to_json};
+use crate::markdown::{parse_markdown, to_markdown};
+use crate::model::BayesData;
+use crate::strength::StrengthUnit;
+use serde::{Deserialize, Serialize};
+
+/// A file format analyses can be exported to and loaded from.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FileFormat {
+    #[default]
+    Markdown,
+    Json,
+    /// The likelihood matrix only, see `to_csv`.
+    Csv,
+    Tsv,
+}
+
+impl FileFormat {
+    pub const ALL: [FileFormat; 4] = [
+        FileFormat::Markdown,
+        FileFormat::Json,
+        FileFormat::Csv,
+        FileFormat::Tsv,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            FileFormat::Markdown => "Markdown",
+            FileFormat::Json => "JSON",
+            FileFormat::Csv => "CSV",
+            FileFormat::Tsv => "TSV",
+        }
+    }
+
+    /// The inverse of `name`, ignoring case.
+    pub fn from_name(name: &str) -> Option<FileFormat> {
+        FileFormat::ALL
+            .into_iter()
+            .find(|format| format.name().eq_ignore_ascii_case(name.trim()))
+    }
+
+    /// The extension exported files get, with the dot.
+    pub fn extension(&self) -> &'static str {
+        match self {
+            FileFormat::Markdown => ".bayes.md",
+            FileFormat::Json => ".bayes.json",
+            FileFormat::Csv => ".bayes.csv",
+            FileFormat::Tsv => ".bayes.tsv",
+        }
+    }
+
+    /// Extensions loaded files may have, as for a file input's `accept`.
+    pub fn accepts(&self) -> &'static [&'static str] {
+        match self {
+            FileFormat::Markdown => &[".md"],
+            FileFormat::Json => &[".json"],
+            FileFormat::Csv => &[".csv"],
+            FileFormat::Tsv => &[".tsv", ".tab"],
+        }
+    }
+
+    pub fn mime_type(&self) -> &'static str {
+        match self {
+            FileFormat::Markdown => "text/markdown",
+            FileFormat::Json => "application/json",
+            FileFormat::Csv => "text/csv",
+            FileFormat::Tsv => "text/tab-separated-values",
+        }
+    }
+
+    /// The format a file name's extension says, if it's one of ours.
+    pub fn from_file_name(name: &str) -> Option<FileFormat> {
+        let name = name.to_lowercase();
+        FileFormat::ALL.into_iter().find(|format| {
+            format
+                .accepts()
+                .iter()
+                .any(|extension| name.ends_with(extension))
+        })
+    }
+
+    /// `data` in this format. `unit` is for the evidence strength markdown
+    /// adds for people reading it.
+    pub fn write(&self, data: &BayesData, unit: StrengthUnit) -> String {
+        match self {
+            FileFormat::Markdown => to_markdown(data, unit),
+            FileFormat::Json => to_json(data),
+            FileFormat::Csv => to_csv(data, ','),
+            FileFormat::Tsv => to_csv(data, '\t'),
+        }
+    }
+
+    pub fn parse(&self, content: &str) -> Result<Imported, ImportError> {
+        match self {
+            FileFormat::Markdown => parse_markdown(content),
+            FileFormat::Json => parse_json(content),
+            FileFormat::Csv => parse_csv(content, ','),
+            FileFormat::Tsv => parse_csv(content, '\t'),
+        }
+    }
+}
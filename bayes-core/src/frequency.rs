@@ -0,0 +1,115 @@
+use crate::calc::{log_recalculate_to, normalize};
+use crate::model::BayesData;
+
+/// How many people of some population are left with each hypothesis after
+/// an evidence row, i.e. had the hypothesis and would have shown every piece
+/// of evidence so far.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FrequencyStep {
+    /// The evidence row this step applies, or `None` for the prior.
+    pub evidence: Option<usize>,
+    /// Expected number of people per hypothesis, not rounded.
+    pub counts: Vec<f64>,
+    /// Whole people per hypothesis. Never more than at the step before, so
+    /// each step's people are a subset of the last one's.
+    pub people: Vec<usize>,
+}
+
+/// Splits `total` into whole numbers in proportion to `weights`, giving the
+/// leftovers to the largest remainders.
+fn allocate(weights: &[f64], total: usize) -> Vec<usize> {
+    let shares: Vec<f64> = normalize(weights.to_vec())
+        .iter()
+        .map(|w| w * total as f64)
+        .collect();
+    let mut people: Vec<usize> = shares.iter().map(|s| s.floor() as usize).collect();
+    let mut order: Vec<usize> = (0..shares.len()).collect();
+    order.sort_by(|a, b| {
+        let remainder = |idx: usize| shares[idx] - shares[idx].floor();
+        remainder(*b).total_cmp(&remainder(*a))
+    });
+    let allocated: usize = people.iter().sum();
+    if shares.iter().any(|s| *s > 0.0) {
+        for idx in order.into_iter().take(total.saturating_sub(allocated)) {
+            people[idx] += 1;
+        }
+    }
+    people
+}
+
+/// The prior and then every row that counts towards the posterior, as
+/// natural frequencies out of `population` people.
+pub fn natural_frequencies(data: &BayesData, population: usize) -> Vec<FrequencyStep> {
+    let prior = normalize(data.prior_odds.clone());
+    let likelihoods = data.effective_likelihoods();
+    let counts_to = |to: usize| -> Vec<f64> {
+        log_recalculate_to(&prior, &likelihoods, to)
+            .iter()
+            .map(|log| population as f64 * log.exp())
+            .collect()
+    };
+
+    let first = allocate(&prior, population);
+    let mut steps = vec![FrequencyStep {
+        evidence: None,
+        counts: counts_to(0),
+        people: first,
+    }];
+    for ev_idx in (0..likelihoods.len()).filter(|ev_idx| data.counts(*ev_idx)) {
+        let counts = counts_to(ev_idx + 1);
+        let before = &steps.last().unwrap().people;
+        let people = counts
+            .iter()
+            .zip(before)
+            .map(|(count, before)| (count.round() as usize).min(*before))
+            .collect();
+        steps.push(FrequencyStep {
+            evidence: Some(ev_idx),
+            counts,
+            people,
+        });
+    }
+    steps
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn leftovers_go_to_the_largest_remainders() {
+        assert_eq!(allocate(&[0.125, 0.875], 10), vec![1, 9]);
+        assert_eq!(allocate(&[1.0, 2.0, 2.0], 10), vec![2, 4, 4]);
+        // Equal remainders go in order, and the total is always kept.
+        assert_eq!(allocate(&[1.0, 1.0, 1.0], 100), vec![34, 33, 33]);
+        assert_eq!(allocate(&[0.0, 0.0], 100), vec![0, 0]);
+    }
+
+    #[test]
+    fn each_step_keeps_a_subset_of_the_last() {
+        let mut data = BayesData {
+            hypotheses: vec!["A".to_string(), "B".to_string()],
+            prior_odds: vec![1.0, 3.0],
+            evidence: vec!["E".to_string(), "F".to_string(), "G".to_string()],
+            likelihoods: vec![vec![0.5, 0.1], vec![0.2, 0.2], vec![0.9, 0.9]],
+            active: vec![true, false, true],
+            ..BayesData::default()
+        };
+        data.set_planned(2, true);
+        let steps = natural_frequencies(&data, 100);
+        // Only rows that count are steps.
+        let evidence: Vec<Option<usize>> = steps.iter().map(|step| step.evidence).collect();
+        assert_eq!(evidence, vec![None, Some(0)]);
+        assert_eq!(steps[0].people, vec![25, 75]);
+        assert!((steps[1].counts[0] - 12.5).abs() < 1e-9);
+        assert!((steps[1].counts[1] - 7.5).abs() < 1e-9);
+        assert_eq!(steps[1].people, vec![13, 8]);
+
+        // Half a person each rounds up to one, but B had none to start with.
+        data.likelihoods[0] = vec![1.0, 1.0];
+        data.prior_odds = vec![1.0, 1.0];
+        let steps = natural_frequencies(&data, 1);
+        assert_eq!(steps[0].people, vec![1, 0]);
+        assert_eq!(steps[1].people, vec![1, 0]);
+    }
+}
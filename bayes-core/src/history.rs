@@ -0,0 +1,111 @@
+use serde::{Deserialize, Serialize};
+
+const MAX_STEPS: usize = 100;
+
+/// Undo/redo stacks of snapshots. Consecutive edits recorded with the same
+/// key (e.g. one slider being dragged) are coalesced into a single step.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct History<T, K> {
+    undo: Vec<T>,
+    redo: Vec<T>,
+    #[serde(skip, default = "no_key")]
+    last_key: Option<K>,
+}
+
+/// A loaded history starts a new run of edits. Unlike `#[serde(skip)]` alone,
+/// this doesn't need `K: Default`.
+fn no_key<K>() -> Option<K> {
+    None
+}
+
+impl<T, K: PartialEq> History<T, K> {
+    pub fn new() -> Self {
+        Self {
+            undo: Vec::new(),
+            redo: Vec::new(),
+            last_key: None,
+        }
+    }
+
+    /// Records `before`, the state as it was before an edit. Returns whether
+    /// the stacks changed, which a coalesced edit only does at its start.
+    pub fn record(&mut self, before: T, key: Option<K>) -> bool {
+        let cleared = !self.redo.is_empty();
+        self.redo.clear();
+        if key.is_some() && key == self.last_key {
+            return cleared;
+        }
+        self.undo.push(before);
+        if self.undo.len() > MAX_STEPS {
+            self.undo.remove(0);
+        }
+        self.last_key = key;
+        true
+    }
+
+    /// Ends the current run of coalesced edits, so the next edit is its own step.
+    pub fn break_coalescing(&mut self) {
+        self.last_key = None;
+    }
+
+    pub fn undo(&mut self, current: T) -> Option<T> {
+        let previous = self.undo.pop()?;
+        self.redo.push(current);
+        self.last_key = None;
+        Some(previous)
+    }
+
+    pub fn redo(&mut self, current: T) -> Option<T> {
+        let next = self.redo.pop()?;
+        self.undo.push(current);
+        self.last_key = None;
+        Some(next)
+    }
+
+    pub fn can_undo(&self) -> bool {
+        !self.undo.is_empty()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.redo.is_empty()
+    }
+}
+
+impl<T, K: PartialEq> Default for History<T, K> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn coalesced_edits_only_change_the_history_once() {
+        let mut history: History<u32, &str> = History::new();
+        assert!(history.record(0, Some("slider")));
+        assert!(!history.record(1, Some("slider")));
+        assert!(!history.record(2, Some("slider")));
+        assert!(history.record(3, Some("other")));
+        history.break_coalescing();
+        assert!(history.record(4, Some("other")));
+        assert_eq!(history.undo(5), Some(4));
+        // Clearing the redo stack is a change, even mid-drag.
+        assert!(history.record(4, Some("other")));
+        assert!(!history.record(5, Some("other")));
+    }
+
+    #[test]
+    fn keeps_at_most_max_steps() {
+        let mut history: History<usize, ()> = History::new();
+        for step in 0..MAX_STEPS + 10 {
+            history.record(step, None);
+        }
+        let mut undone = 0;
+        while history.undo(0).is_some() {
+            undone += 1;
+        }
+        assert_eq!(undone, MAX_STEPS);
+    }
+}
@@ -0,0 +1,76 @@
+use crate::calc::{percentize, recalculate};
+use crate::model::BayesData;
+
+/// What the posterior would be without one evidence row.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Influence {
+    /// Posterior in percent with the row left out.
+    pub posterior_without: Vec<f64>,
+    /// The largest change, in percentage points, of any hypothesis's posterior.
+    pub shift: f64,
+}
+
+/// Leave-one-out influence of each evidence row on the final posterior.
+/// Inactive and planned rows already don't count, so their shift is 0.
+pub fn evidence_influence(data: &BayesData) -> Vec<Influence> {
+    let likelihoods = data.effective_likelihoods();
+    let full = percentize(recalculate(data.prior_odds.clone(), likelihoods.clone()));
+    (0..likelihoods.len())
+        .map(|ev_idx| {
+            let mut without = likelihoods.clone();
+            without.remove(ev_idx);
+            let posterior_without = percentize(recalculate(data.prior_odds.clone(), without));
+            let shift = full
+                .iter()
+                .zip(&posterior_without)
+                .map(|(a, b)| (a - b).abs())
+                .fold(0.0, f64::max);
+            Influence {
+                posterior_without,
+                shift,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn leaving_out_a_row_shows_its_shift() {
+        let data = BayesData {
+            hypotheses: vec!["A".to_string(), "B".to_string()],
+            prior_odds: vec![1.0, 1.0],
+            evidence: vec![
+                "Strong".to_string(),
+                "Even".to_string(),
+                "Planned".to_string(),
+            ],
+            likelihoods: vec![vec![0.8, 0.2], vec![0.5, 0.5], vec![0.9, 0.1]],
+            planned: vec![false, false, true],
+            ..BayesData::default()
+        };
+        // The posterior is 80% : 20%, all from the first row.
+        let influence = evidence_influence(&data);
+        assert_eq!(influence[0].posterior_without, vec![50.0, 50.0]);
+        assert!((influence[0].shift - 30.0).abs() < 1e-9);
+        assert!(influence[1].shift.abs() < 1e-9);
+        // A planned row doesn't count, so leaving it out changes nothing.
+        assert_eq!(influence[2].shift, 0.0);
+    }
+
+    #[test]
+    fn a_row_that_rules_out_a_hypothesis_shifts_it_all() {
+        let data = BayesData {
+            hypotheses: vec!["A".to_string(), "B".to_string()],
+            prior_odds: vec![1.0, 3.0],
+            evidence: vec!["Proof".to_string()],
+            likelihoods: vec![vec![1.0, 0.0]],
+            ..BayesData::default()
+        };
+        let influence = evidence_influence(&data);
+        assert_eq!(influence[0].posterior_without, vec![25.0, 75.0]);
+        assert_eq!(influence[0].shift, 75.0);
+    }
+}
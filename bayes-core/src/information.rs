@@ -0,0 +1,213 @@
+use crate::calc::{normalize, percentize, recalculate_to};
+use crate::evidence::EvidenceKind;
+use crate::model::BayesData;
+
+/// Measured values are split into this many bins when forecasting.
+const CONTINUOUS_BINS: usize = 50;
+
+/// One way a planned evidence row could turn out.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Forecast {
+    pub outcome: String,
+    /// Predictive probability of this outcome under the current posterior.
+    pub probability: f64,
+    /// Posterior in percent if this outcome were observed.
+    pub posterior: Vec<f64>,
+}
+
+/// How much observing a planned evidence row is expected to tell us.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ValueOfInformation {
+    pub forecasts: Vec<Forecast>,
+    /// Expected drop in posterior entropy, in bits.
+    pub entropy_reduction: f64,
+    /// Probability that a different hypothesis leads once it's observed.
+    pub leader_change: f64,
+}
+
+/// Shannon entropy in bits of weights that needn't sum to 1.
+pub fn entropy_bits(weights: &[f64]) -> f64 {
+    let total: f64 = weights.iter().sum();
+    if total <= 0.0 {
+        return 0.0;
+    }
+    weights
+        .iter()
+        .map(|w| w / total)
+        .filter(|p| *p > 0.0)
+        .map(|p| -p * p.log2())
+        .sum()
+}
+
+fn leader(weights: &[f64]) -> usize {
+    weights.iter().enumerate().fold(
+        0,
+        |best, (idx, w)| if *w > weights[best] { idx } else { best },
+    )
+}
+
+/// Each possible outcome of a row and its likelihood under every hypothesis.
+fn outcome_likelihoods(data: &BayesData, ev_idx: usize) -> Vec<(String, Vec<f64>)> {
+    match data.kind(ev_idx) {
+        EvidenceKind::Binary => {
+            let row = &data.likelihoods[ev_idx];
+            vec![
+                ("Yes".to_string(), row.clone()),
+                ("No".to_string(), row.iter().map(|p| 1.0 - p).collect()),
+            ]
+        }
+        EvidenceKind::Categorical(categorical) => categorical
+            .outcomes
+            .iter()
+            .enumerate()
+            .map(|(outcome_idx, outcome)| {
+                let column = categorical
+                    .table
+                    .iter()
+                    .map(|row| row[outcome_idx])
+                    .collect();
+                (outcome.clone(), column)
+            })
+            .collect(),
+        EvidenceKind::Continuous(continuous) => {
+            let (low, high) = continuous.range();
+            let width = (high - low) / CONTINUOUS_BINS as f64;
+            (0..CONTINUOUS_BINS)
+                .map(|bin| {
+                    let start = low + width * bin as f64;
+                    let mid = start + 0.5 * width;
+                    let column = continuous
+                        .distributions
+                        .iter()
+                        .map(|dist| dist.density(mid) * width)
+                        .collect();
+                    (format!("{:.3} – {:.3}", start, start + width), column)
+                })
+                .collect()
+        }
+    }
+}
+
+/// Forecasts each outcome of the planned row at `ev_idx` from the posterior of
+/// every row that counts, using `recalculate_to` with the row swapped for that
+/// outcome's likelihoods. The row itself never enters the current posterior.
+pub fn value_of_information(data: &BayesData, ev_idx: usize) -> ValueOfInformation {
+    let mut likelihoods = data.effective_likelihoods();
+    let to = likelihoods.len();
+    let current = normalize(recalculate_to(
+        data.prior_odds.clone(),
+        likelihoods.clone(),
+        to,
+    ));
+    let current_leader = leader(&current);
+
+    let mut forecasts: Vec<Forecast> = outcome_likelihoods(data, ev_idx)
+        .into_iter()
+        .map(|(outcome, column)| {
+            let probability = current.iter().zip(&column).map(|(p, l)| p * l).sum();
+            likelihoods[ev_idx] = column;
+            let posterior = percentize(recalculate_to(
+                data.prior_odds.clone(),
+                likelihoods.clone(),
+                to,
+            ));
+            Forecast {
+                outcome,
+                probability,
+                posterior,
+            }
+        })
+        .collect();
+    // Binned measurements lose their tails, so rescale to a full distribution.
+    let total: f64 = forecasts.iter().map(|f| f.probability).sum();
+    if total > 0.0 {
+        for forecast in forecasts.iter_mut() {
+            forecast.probability /= total;
+        }
+    }
+
+    let expected_entropy: f64 = forecasts
+        .iter()
+        .filter(|f| f.probability > 0.0)
+        .map(|f| f.probability * entropy_bits(&f.posterior))
+        .sum();
+    let leader_change = forecasts
+        .iter()
+        .filter(|f| f.probability > 0.0 && leader(&f.posterior) != current_leader)
+        .map(|f| f.probability)
+        .sum();
+
+    ValueOfInformation {
+        forecasts,
+        entropy_reduction: (entropy_bits(&current) - expected_entropy).max(0.0),
+        leader_change,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::distribution::Distribution;
+    use crate::evidence::Continuous;
+
+    /// Two equally likely hypotheses and one planned binary row.
+    fn planned(likelihoods: Vec<f64>) -> BayesData {
+        let mut data = BayesData {
+            hypotheses: vec!["A".to_string(), "B".to_string()],
+            prior_odds: vec![1.0, 1.0],
+            evidence: vec!["Test".to_string()],
+            likelihoods: vec![likelihoods],
+            active: vec![true],
+            ..BayesData::default()
+        };
+        data.set_planned(0, true);
+        data
+    }
+
+    #[test]
+    fn a_telling_row_lowers_entropy_and_may_change_the_leader() {
+        let voi = value_of_information(&planned(vec![0.9, 0.1]), 0);
+        let outcomes: Vec<(&str, f64)> = voi
+            .forecasts
+            .iter()
+            .map(|f| (f.outcome.as_str(), f.probability))
+            .collect();
+        assert_eq!(outcomes, vec![("Yes", 0.5), ("No", 0.5)]);
+        assert!((voi.forecasts[0].posterior[0] - 90.0).abs() < 1e-9);
+        // 1 bit before, and H(0.9) = 0.469 bits after either outcome.
+        assert!((voi.entropy_reduction - 0.531).abs() < 0.001);
+        assert_eq!(voi.leader_change, 0.5);
+    }
+
+    #[test]
+    fn a_row_that_cannot_tell_the_hypotheses_apart_is_worth_nothing() {
+        let voi = value_of_information(&planned(vec![0.3, 0.3]), 0);
+        assert_eq!(voi.entropy_reduction, 0.0);
+        assert_eq!(voi.leader_change, 0.0);
+        // Value is never negative, even when an outcome spreads the posterior out.
+        let mut data = planned(vec![0.9, 0.5]);
+        data.prior_odds = vec![1.0, 9.0];
+        assert!(value_of_information(&data, 0).entropy_reduction >= 0.0);
+    }
+
+    #[test]
+    fn measurements_are_forecast_in_bins() {
+        let mut data = planned(vec![1.0, 1.0]);
+        data.set_kind(
+            0,
+            EvidenceKind::Continuous(Continuous {
+                distributions: vec![
+                    Distribution::Normal { mean: 0.0, sd: 1.0 },
+                    Distribution::Normal { mean: 3.0, sd: 1.0 },
+                ],
+                observed: 0.0,
+            }),
+        );
+        let voi = value_of_information(&data, 0);
+        assert_eq!(voi.forecasts.len(), CONTINUOUS_BINS);
+        let total: f64 = voi.forecasts.iter().map(|f| f.probability).sum();
+        assert!((total - 1.0).abs() < 1e-9);
+        assert!(voi.entropy_reduction > 0.5 && voi.entropy_reduction < 1.0);
+        assert!((voi.leader_change - 0.5).abs() < 0.05);
+    }
+}
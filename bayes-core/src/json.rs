@@ -0,0 +1,227 @@
+use crate::calc::{percentize, recalculate};
+use crate::diagnostic::{Diagnostic, ImportError, Imported};
+use crate::model::BayesData;
+use crate::validate::{check_data, check_posterior};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// The version of the JSON format `to_json` writes. Its schema is published
+/// at `JSON_SCHEMA_URL`, from `static/bayes-calc.schema.json`.
+pub const JSON_VERSION: u32 = 1;
+pub const JSON_SCHEMA_URL: &str = "https://bayescalc.io/static/bayes-calc.schema.json";
+const FORMAT: &str = "bayes-calc";
+
+/// The JSON document: a header saying what it is, and the analysis as it's
+/// serialised everywhere else, e.g. in share links.
+#[derive(Serialize, Deserialize)]
+struct JsonDocument {
+    #[serde(rename = "$schema", default, skip_serializing_if = "Option::is_none")]
+    schema: Option<String>,
+    format: String,
+    version: u32,
+    analysis: BayesData,
+}
+
+pub fn to_json(data: &BayesData) -> String {
+    let document = JsonDocument {
+        schema: Some(JSON_SCHEMA_URL.to_string()),
+        format: FORMAT.to_string(),
+        version: JSON_VERSION,
+        analysis: data.clone(),
+    };
+    // Every field is a plain value or a list, which always serialise.
+    serde_json::to_string_pretty(&document).unwrap()
+}
+
+/// A serde_json error, placed at the line and column it gives.
+fn syntax_error(content: &str, err: &serde_json::Error, fix: &str) -> Diagnostic {
+    let line = content
+        .lines()
+        .nth(err.line().saturating_sub(1))
+        .unwrap_or("");
+    // The column is in bytes, and may point just past the end of the line.
+    let mut offset = err.column().saturating_sub(1).min(line.len());
+    while !line.is_char_boundary(offset) {
+        offset -= 1;
+    }
+    let rest = &line[offset..];
+    let end = rest
+        .find([',', '}', ']'])
+        .unwrap_or(rest.len())
+        .max(rest.chars().next().map_or(0, char::len_utf8));
+    // serde_json ends its messages with " at line 1 column 2", which the
+    // diagnostic already says.
+    let message = err.to_string();
+    let message = message
+        .rsplit_once(" at line ")
+        .map_or(message.as_str(), |(message, _)| message);
+    Diagnostic::at(err.line(), line, offset, &rest[..end], message, fix)
+}
+
+/// Checks the `format` and `version` header before the rest, so a file from
+/// a newer version gets one clear error rather than one per unknown field.
+fn check_header(value: &Value) -> Result<(), Diagnostic> {
+    if value.get("format").and_then(Value::as_str) != Some(FORMAT) {
+        return Err(Diagnostic::in_field(
+            "format".to_string(),
+            "This isn't a bayes-calc analysis",
+            format!(
+                "Start the document with \"format\": \"{}\", \"version\": {}",
+                FORMAT, JSON_VERSION
+            ),
+        ));
+    }
+    match value.get("version").and_then(Value::as_u64) {
+        None => Err(Diagnostic::in_field(
+            "version".to_string(),
+            "The document has no version",
+            format!("Add \"version\": {}", JSON_VERSION),
+        )),
+        Some(version) if version > JSON_VERSION as u64 => Err(Diagnostic::in_field(
+            "version".to_string(),
+            format!(
+                "This file is format version {}, but only versions up to {} can be read",
+                version, JSON_VERSION
+            ),
+            "Update bayescalc, or save the file from the newer version in an older format",
+        )),
+        Some(_) => Ok(()),
+    }
+}
+
+pub fn parse_json(content: &str) -> Result<Imported, ImportError> {
+    let value: Value = serde_json::from_str(content)
+        .map_err(|err| syntax_error(content, &err, "Fix the JSON syntax here"))?;
+    check_header(&value)?;
+    let document: JsonDocument = serde_json::from_str(content).map_err(|err| {
+        syntax_error(
+            content,
+            &err,
+            &format!("Check this against the schema at {}", JSON_SCHEMA_URL),
+        )
+    })?;
+
+    let mut data = document.analysis;
+    let diagnostics = check_data(&data);
+    if !diagnostics.is_empty() {
+        return Err(ImportError { diagnostics });
+    }
+    for ev_idx in 0..data.evidence.len() {
+        data.sync_likelihoods(ev_idx);
+    }
+    let stated = std::mem::take(&mut data.posterior_odds);
+    data.posterior_odds = percentize(recalculate(
+        data.prior_odds.clone(),
+        data.effective_likelihoods(),
+    ));
+    // As for markdown, a posterior that's given is checked but not used.
+    let warnings = stated
+        .iter()
+        .zip(&data.posterior_odds)
+        .enumerate()
+        .filter_map(|(hyp_idx, (stated, recalculated))| {
+            let invalid = check_posterior(*stated, *recalculated).err()?;
+            let path = format!("posterior_odds[{}]", hyp_idx);
+            Some(Diagnostic::in_field(path, invalid.message, invalid.fix).warning())
+        })
+        .collect();
+
+    Ok(Imported { data, warnings })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A and B with priors giving posteriors of 25% and 75%, and `posterior`
+    /// as the stated one.
+    fn stated(posterior: Vec<f64>) -> String {
+        to_json(&BayesData {
+            hypotheses: vec!["A".to_string(), "B".to_string()],
+            prior_odds: vec![1.0, 3.0],
+            posterior_odds: posterior,
+            ..BayesData::default()
+        })
+    }
+
+    fn texts(diagnostics: Vec<Diagnostic>) -> Vec<String> {
+        diagnostics
+            .into_iter()
+            .map(|diagnostic| diagnostic.text)
+            .collect()
+    }
+
+    #[test]
+    fn round_trips() {
+        let mut data = BayesData {
+            hypotheses: vec!["Rain".to_string(), "No \"rain\"".to_string()],
+            prior_odds: vec![1.0, 3.5],
+            evidence: vec!["Clouds".to_string(), "Forecast".to_string()],
+            likelihoods: vec![vec![0.9, 0.35], vec![0.7, 0.1]],
+            active: vec![true, false],
+            planned: vec![false, true],
+            actions: vec!["Umbrella".to_string()],
+            utilities: vec![vec![5.0, -1.0]],
+            ..BayesData::default()
+        };
+        data.posterior_odds = percentize(recalculate(
+            data.prior_odds.clone(),
+            data.effective_likelihoods(),
+        ));
+        let imported = parse_json(&to_json(&data)).unwrap();
+        assert!(imported.warnings.is_empty());
+        assert_eq!(imported.data, data);
+    }
+
+    fn error(content: &str) -> Diagnostic {
+        let mut diagnostics = parse_json(content).unwrap_err().diagnostics;
+        assert_eq!(diagnostics.len(), 1);
+        diagnostics.remove(0)
+    }
+
+    #[test]
+    fn rejects_other_formats_and_newer_versions() {
+        let wrong = error(r#"{"format": "other", "version": 1, "analysis": {}}"#);
+        assert_eq!(wrong.text, "format");
+        assert_eq!(error(r#"{"version": 1}"#).text, "format");
+        let unversioned = error(r#"{"format": "bayes-calc", "analysis": {}}"#);
+        assert_eq!(unversioned.text, "version");
+        assert_eq!(unversioned.message, "The document has no version");
+        // Only the version is reported, not the fields it doesn't know.
+        let newer = stated(vec![25.0, 75.0])
+            .replace("\"version\": 1", "\"version\": 2")
+            .replace("\"analysis\": {", "\"analysis\": {\"unknown\": [],");
+        let newer = error(&newer);
+        assert_eq!(newer.text, "version");
+        assert_eq!(
+            newer.message,
+            "This file is format version 2, but only versions up to 1 can be read"
+        );
+    }
+
+    #[test]
+    fn places_syntax_errors() {
+        let content = "{\n  \"format\": \"bayes-calc\",\n  \"version\": 1,\n  \"analysis\": {,}\n}";
+        let diagnostic = error(content);
+        assert_eq!(
+            (diagnostic.line, diagnostic.column, diagnostic.text.as_str()),
+            (4, 16, ",")
+        );
+        // A valid document that doesn't fit the schema is placed too.
+        let content = "{\n  \"format\": \"bayes-calc\",\n  \"version\": 1,\n  \"analysis\": {\"hypotheses\": 5}\n}";
+        let diagnostic = error(content);
+        assert_eq!((diagnostic.line, diagnostic.column), (4, 30));
+    }
+
+    #[test]
+    fn stale_posteriors_are_warned_about_at_their_fields() {
+        // The priors give 25% and 75%.
+        let stale = parse_json(&stated(vec![30.0, 75.0])).unwrap();
+        assert_eq!(stale.data.posterior_odds, vec![25.0, 75.0]);
+        assert_eq!(texts(stale.warnings), vec!["posterior_odds[0]"]);
+        // It may be left out, but not cut short.
+        assert!(parse_json(&stated(Vec::new())).is_ok());
+        let short = parse_json(&stated(vec![25.0])).unwrap_err();
+        assert_eq!(texts(short.diagnostics), vec!["posterior_odds"]);
+    }
+}
@@ -0,0 +1,54 @@
+//! The model, math and text formats behind bayescalc.io, with no browser
+//! dependencies.
+
+mod calc;
+mod calibration;
+mod csv;
+mod decimal;
+mod decision;
+mod diagnostic;
+mod distribution;
+mod entry;
+mod evidence;
+mod format;
+mod frequency;
+mod history;
+mod influence;
+mod information;
+mod json;
+mod library;
+mod markdown;
+mod model;
+mod numeric;
+mod sampling;
+mod sensitivity;
+mod strength;
+mod uncertainty;
+mod validate;
+
+pub use calc::{
+    log_odds_in_db, log_recalculate_to, log_sum_exp, normalize, pairwise_log_odds_in_db,
+    pairwise_log_odds_in_db_from_logs, percentize, recalculate, recalculate_to, ruled_out,
+};
+pub use calibration::{CalibrationBin, Prediction, PredictionLog};
+pub use csv::{parse_csv, to_csv};
+pub use decision::{decide, Decision, Threshold};
+pub use diagnostic::{Diagnostic, ImportError, Imported, Severity};
+pub use distribution::Distribution;
+pub use entry::{db_to_ratio, likelihood_ratios, ratio_to_db, with_likelihood_ratio, EntryMode};
+pub use evidence::{Categorical, Continuous, EvidenceKind};
+pub use format::FileFormat;
+pub use frequency::{natural_frequencies, FrequencyStep};
+pub use history::History;
+pub use influence::{evidence_influence, Influence};
+pub use information::{entropy_bits, value_of_information, Forecast, ValueOfInformation};
+pub use json::{parse_json, to_json, JSON_SCHEMA_URL, JSON_VERSION};
+pub use library::{Document, Library};
+pub use markdown::{parse_markdown, to_markdown, MARKDOWN_VERSION};
+pub use model::{hypothesis_letters, BayesData};
+pub use numeric::{parse_quantity, Quantity};
+pub use sampling::Rng;
+pub use sensitivity::{sensitivities, Input, Sensitivity};
+pub use strength::{strongest, StrengthUnit};
+pub use uncertainty::{posterior_intervals, Interval, Spread};
+pub use validate::{check_data, POSTERIOR_TOLERANCE};
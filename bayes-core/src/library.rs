@@ -0,0 +1,144 @@
+use crate::model::BayesData;
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Document {
+    pub id: u64,
+    pub name: String,
+    /// Milliseconds since the Unix epoch.
+    pub modified: f64,
+    pub data: BayesData,
+}
+
+/// A collection of named analyses. Timestamps are passed in by the caller so
+/// this stays independent of any clock.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+pub struct Library {
+    pub documents: Vec<Document>,
+    next_id: u64,
+}
+
+impl Library {
+    pub fn get(&self, id: u64) -> Option<&Document> {
+        self.documents.iter().find(|doc| doc.id == id)
+    }
+
+    fn get_mut(&mut self, id: u64) -> Option<&mut Document> {
+        self.documents.iter_mut().find(|doc| doc.id == id)
+    }
+
+    /// `base`, or `base 2`, `base 3`, ... if that name is already taken.
+    pub fn unique_name(&self, base: &str) -> String {
+        let taken = |name: &str| self.documents.iter().any(|doc| doc.name == name);
+        if !taken(base) {
+            return base.to_string();
+        }
+        (2..)
+            .map(|n| format!("{} {}", base, n))
+            .find(|name| !taken(name))
+            .unwrap()
+    }
+
+    pub fn create(&mut self, name: &str, data: BayesData, now: f64) -> u64 {
+        let id = self.next_id;
+        self.next_id += 1;
+        self.documents.push(Document {
+            id,
+            name: self.unique_name(name),
+            modified: now,
+            data,
+        });
+        id
+    }
+
+    pub fn update(&mut self, id: u64, data: &BayesData, now: f64) {
+        if let Some(doc) = self.get_mut(id) {
+            if doc.data != *data {
+                doc.data = data.clone();
+                doc.modified = now;
+            }
+        }
+    }
+
+    pub fn rename(&mut self, id: u64, name: &str, now: f64) {
+        if let Some(doc) = self.get_mut(id) {
+            doc.name = name.to_string();
+            doc.modified = now;
+        }
+    }
+
+    pub fn duplicate(&mut self, id: u64, now: f64) -> Option<u64> {
+        let doc = self.get(id)?.clone();
+        Some(self.create(&format!("{} (copy)", doc.name), doc.data, now))
+    }
+
+    pub fn delete(&mut self, id: u64) {
+        self.documents.retain(|doc| doc.id != id);
+    }
+
+    /// Documents with the most recently modified first.
+    pub fn recent(&self) -> Vec<&Document> {
+        let mut documents: Vec<&Document> = self.documents.iter().collect();
+        documents.sort_by(|a, b| b.modified.total_cmp(&a.modified));
+        documents
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn data(prior: f64) -> BayesData {
+        BayesData {
+            hypotheses: vec!["A".to_string(), "B".to_string()],
+            prior_odds: vec![prior, 1.0],
+            ..BayesData::default()
+        }
+    }
+
+    #[test]
+    fn names_are_made_unique() {
+        let mut library = Library::default();
+        library.create("Analysis", data(1.0), 0.0);
+        let second = library.create("Analysis", data(1.0), 0.0);
+        let third = library.create("Analysis", data(1.0), 0.0);
+        assert_eq!(library.get(second).unwrap().name, "Analysis 2");
+        assert_eq!(library.get(third).unwrap().name, "Analysis 3");
+        let copy = library.duplicate(second, 0.0).unwrap();
+        assert_eq!(library.get(copy).unwrap().name, "Analysis 2 (copy)");
+        assert_eq!(library.duplicate(99, 0.0), None);
+    }
+
+    #[test]
+    fn ids_are_not_reused_after_a_delete() {
+        let mut library = Library::default();
+        let first = library.create("One", data(1.0), 0.0);
+        library.delete(first);
+        assert!(library.get(first).is_none());
+        assert_ne!(library.create("Two", data(1.0), 0.0), first);
+    }
+
+    #[test]
+    fn only_changes_touch_the_modified_time() {
+        let mut library = Library::default();
+        let id = library.create("One", data(1.0), 10.0);
+        library.update(id, &data(1.0), 20.0);
+        assert_eq!(library.get(id).unwrap().modified, 10.0);
+        library.update(id, &data(2.0), 30.0);
+        assert_eq!(library.get(id).unwrap().modified, 30.0);
+        assert_eq!(library.get(id).unwrap().data, data(2.0));
+        library.rename(id, "Renamed", 40.0);
+        assert_eq!(library.get(id).unwrap().name, "Renamed");
+        assert_eq!(library.get(id).unwrap().modified, 40.0);
+    }
+
+    #[test]
+    fn recent_puts_the_latest_first() {
+        let mut library = Library::default();
+        let old = library.create("Old", data(1.0), 10.0);
+        let new = library.create("New", data(1.0), 30.0);
+        let middle = library.create("Middle", data(1.0), 20.0);
+        let ids: Vec<u64> = library.recent().iter().map(|doc| doc.id).collect();
+        assert_eq!(ids, vec![new, middle, old]);
+    }
+}
@@ -0,0 +1,982 @@
+use crate::calc::{log_odds_in_db, percentize, recalculate};
+use crate::decimal::format_percent;
+use crate::decision::decide;
+use crate::diagnostic::{Diagnostic, ImportError, Imported, Located};
+use crate::distribution::Distribution;
+use crate::evidence::{Categorical, Continuous, EvidenceKind};
+use crate::model::BayesData;
+use crate::strength::{strongest, StrengthUnit};
+use crate::uncertainty::Spread;
+use crate::validate::{
+    check_new_hypothesis, check_outcome_total, check_posterior, check_prior, check_utility,
+    parse_likelihood, parse_number,
+};
+use std::fmt;
+use std::str::FromStr;
+
+/// The format version `Display` writes in the front matter. Files without
+/// front matter are version 1, from before versioning; they parse the same.
+/// Version 3 escapes markup in evidence labels, see `escape_label`, and
+/// version 4 in hypothesis, action and outcome names too.
+pub const MARKDOWN_VERSION: u32 = 4;
+
+/// The first version that escapes evidence labels.
+const ESCAPED_LABELS_VERSION: u32 = 3;
+
+/// The first version that escapes hypothesis, action and outcome names.
+const ESCAPED_NAMES_VERSION: u32 = 4;
+
+/// Puts a backslash before each character that would otherwise be read as
+/// markup in an evidence label or a name, e.g. `_Draft_` as
+/// `\_Draft\_`.
+fn escape_label(label: &str) -> String {
+    let mut escaped = String::with_capacity(label.len());
+    for c in label.chars() {
+        if matches!(c, '\\' | '_' | '~' | '{' | '}' | '#' | '|' | '*') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+/// The inverse of `escape_label`.
+fn unescape_label(label: &str) -> String {
+    let mut unescaped = String::with_capacity(label.len());
+    let mut chars = label.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => unescaped.extend(chars.next()),
+            _ => unescaped.push(c),
+        }
+    }
+    unescaped
+}
+
+/// Whether `text` ends in a backslash that escapes whatever follows it.
+fn ends_escaped(text: &str) -> bool {
+    text.chars().rev().take_while(|c| *c == '\\').count() % 2 == 1
+}
+
+/// A hypothesis, action or outcome name as written, unescaped if it's
+/// `escaped`.
+fn read_name(name: &str, escaped: bool) -> String {
+    if escaped {
+        unescape_label(name)
+    } else {
+        name.to_string()
+    }
+}
+
+/// Splits `text` at each `separator` that isn't escaped.
+fn split_unescaped(text: &str, separator: char) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut start = 0;
+    let mut escaped = false;
+    for (idx, c) in text.char_indices() {
+        if escaped {
+            escaped = false;
+        } else if c == '\\' {
+            escaped = true;
+        } else if c == separator {
+            parts.push(&text[start..idx]);
+            start = idx + c.len_utf8();
+        }
+    }
+    parts.push(&text[start..]);
+    parts
+}
+
+/// An evidence label, escaped, with how it counts marked up, see `mark`.
+fn mark_label(data: &BayesData, ev_idx: usize) -> String {
+    mark(escape_label(&data.evidence[ev_idx]), data, ev_idx)
+}
+
+/// `label` with how the evidence at `ev_idx` counts marked up: struck
+/// through, ~~Label~~, when inactive, and in italics, _Label_, when planned.
+pub(crate) fn mark(mut label: String, data: &BayesData, ev_idx: usize) -> String {
+    if data.is_planned(ev_idx) {
+        label = format!("_{}_", label);
+    }
+    if !data.is_active(ev_idx) {
+        label = format!("~~{}~~", label);
+    }
+    label
+}
+
+/// The inverse of `mark_label`: the label, and whether it's inactive and
+/// whether it's planned. Labels from before `ESCAPED_LABELS_VERSION` aren't
+/// `escaped`.
+pub(crate) fn unmark_label(mut label: &str, escaped: bool) -> (String, bool, bool) {
+    let inactive = label.len() > 4 && label.starts_with("~~") && label.ends_with("~~");
+    if inactive {
+        label = &label[2..label.len() - 2];
+    }
+    let planned = label.len() > 2 && label.starts_with('_') && label.ends_with('_');
+    if planned {
+        label = &label[1..label.len() - 1];
+    }
+    let label = if escaped {
+        unescape_label(label)
+    } else {
+        label.to_string()
+    };
+    (label, inactive, planned)
+}
+
+/// Splits `Name: value` at its last colon, so names may contain colons.
+fn split_line(at: Located<'_>) -> Result<(Located<'_>, Located<'_>), Diagnostic> {
+    let (name, value) = at.rsplit_once(':').ok_or_else(|| {
+        at.trim().error(
+            "Expected a name and a value",
+            "Put a colon between them, e.g. 'A: 50%'",
+        )
+    })?;
+    Ok((name.trim(), value.trim()))
+}
+
+/// Splits `50% [40%, 60%]` into the point value and its optional spread.
+fn split_spread(at: Located<'_>, scale: f64) -> Result<(Located<'_>, Option<Spread>), Diagnostic> {
+    let space = at.text.char_indices().find(|(_, c)| c.is_whitespace());
+    match space.map(|(idx, c)| at.split_around(idx, c.len_utf8())) {
+        Some((point, spread)) => {
+            let spread = spread.trim();
+            let parsed = Spread::parse(spread.text, scale).map_err(|message| {
+                spread.error(
+                    message,
+                    "Write the spread as a range like [40%, 60%] or as Beta(2, 3)",
+                )
+            })?;
+            Ok((point, parsed))
+        }
+        None => Ok((at, None)),
+    }
+}
+
+/// Splits the kind off an evidence label: `Label {low | *medium* | high}` is
+/// categorical with the observed outcome starred, `Label {= 5.2}` is a
+/// measurement of 5.2, and anything else is binary. Escaped braces, `\{`,
+/// are part of the label. Outcome names from before `ESCAPED_NAMES_VERSION`
+/// aren't `escaped`.
+fn split_kind(at: Located<'_>, escaped: bool) -> Result<(Located<'_>, EvidenceKind), Diagnostic> {
+    let Some((label, inner)) = at
+        .strip_suffix('}')
+        .filter(|rest| !ends_escaped(rest.text))
+        .and_then(|rest| {
+            let idx = rest
+                .text
+                .rmatch_indices('{')
+                .map(|(idx, _)| idx)
+                .find(|&idx| !ends_escaped(&rest.text[..idx]))?;
+            Some(rest.split_around(idx, 1))
+        })
+    else {
+        return Ok((at, EvidenceKind::Binary));
+    };
+    let label = label.trim_end();
+    if let Some(observed) = inner.trim().strip_prefix('=') {
+        let observed = parse_number(observed.trim(), "{= 5.2}")?;
+        return Ok((
+            label,
+            EvidenceKind::Continuous(Continuous {
+                distributions: Vec::new(),
+                observed,
+            }),
+        ));
+    }
+    let mut names = Vec::new();
+    let mut observed = None;
+    let outcomes = if escaped {
+        split_unescaped(inner.text, '|')
+    } else {
+        inner.text.split('|').collect()
+    };
+    for (idx, outcome) in outcomes.into_iter().map(str::trim).enumerate() {
+        let starred = outcome
+            .strip_prefix('*')
+            .and_then(|rest| rest.strip_suffix('*'))
+            .filter(|name| !(escaped && ends_escaped(name)));
+        if starred.is_some() {
+            observed = Some(idx);
+        }
+        let name = starred.unwrap_or(outcome);
+        names.push(read_name(name, escaped));
+    }
+    let observed = observed.ok_or_else(|| {
+        inner.error(
+            "No outcome is marked as observed",
+            "Star the outcome that was observed, e.g. {low | *high*}",
+        )
+    })?;
+    Ok((
+        label,
+        EvidenceKind::Categorical(Categorical {
+            outcomes: names,
+            table: Vec::new(),
+            observed,
+        }),
+    ))
+}
+
+/// Reads the `---` front matter, if any, returning the format version and
+/// how many lines it took up. Only a version newer than this one stops the
+/// rest of the file being read; other problems are added to `diagnostics`.
+fn front_matter(
+    lines: &[&str],
+    diagnostics: &mut Vec<Diagnostic>,
+) -> Result<(u32, usize), Diagnostic> {
+    if lines.first().map(|line| line.trim()) != Some("---") {
+        return Ok((1, 0));
+    }
+    let mut version = None;
+    for (idx, line) in lines.iter().enumerate().skip(1) {
+        let at = Located::line(idx + 1, line);
+        if line.trim() == "---" {
+            if version.is_none() {
+                diagnostics.push(at.error(
+                    "The front matter has no version",
+                    format!("Add 'version: {}' above this line", MARKDOWN_VERSION),
+                ));
+            }
+            return Ok((version.unwrap_or(MARKDOWN_VERSION), idx + 1));
+        }
+        let Some((key, value)) = at.split_once(':') else {
+            continue;
+        };
+        if key.text.trim() != "version" {
+            continue;
+        }
+        let value = value.trim();
+        match value.text.parse::<u32>() {
+            Ok(parsed) if parsed > MARKDOWN_VERSION => {
+                return Err(value.error(
+                    format!(
+                        "This file is format version {}, but only versions up to {} can be read",
+                        parsed, MARKDOWN_VERSION
+                    ),
+                    "Update bayescalc, or save the file from the newer version in an older format",
+                ))
+            }
+            Ok(parsed) => version = Some(parsed),
+            Err(_) => diagnostics.push(value.error(
+                "This isn't a version number",
+                format!(
+                    "Write the version as a whole number, e.g. 'version: {}'",
+                    MARKDOWN_VERSION
+                ),
+            )),
+        }
+    }
+    diagnostics.push(Located::line(1, lines[0]).error(
+        "The front matter is never closed",
+        "Add a '---' line after the last front matter line",
+    ));
+    Ok((1, 1))
+}
+
+/// A `###` block, or the posterior, and the hypothesis names listed in it.
+struct Block<'a> {
+    header: Located<'a>,
+    names: Vec<Located<'a>>,
+}
+
+/// Checks that a block lists exactly the hypotheses from `## Prior`, in order.
+fn check_names(block: &Block<'_>, hypotheses: &[Located<'_>], diagnostics: &mut Vec<Diagnostic>) {
+    for (idx, name) in block.names.iter().enumerate() {
+        match hypotheses.get(idx) {
+            Some(expected) if expected.text == name.text => {}
+            Some(expected) => diagnostics.push(name.error(
+                format!("Expected hypothesis '{}' here", expected.text),
+                "List the hypotheses in the same order and with the same names as in ## Prior",
+            )),
+            None => diagnostics.push(name.error(
+                "This isn't one of the hypotheses in ## Prior",
+                format!("Add '{}' to ## Prior or remove this line", name.text),
+            )),
+        }
+    }
+    if let Some(missing) = hypotheses.get(block.names.len()) {
+        diagnostics.push(block.header.error(
+            format!(
+                "Only {} of the {} hypotheses are listed under this",
+                block.names.len(),
+                hypotheses.len()
+            ),
+            format!("Add a line for '{}'", missing.text),
+        ));
+    }
+}
+
+/// Warns where the `## Posterior` in the file doesn't match `posterior`, the
+/// one recalculated from it. Its names have already been checked against
+/// `## Prior`.
+fn posterior_warnings(stated: &[Located<'_>], posterior: &[f64]) -> Vec<Diagnostic> {
+    let mut warnings = Vec::new();
+    for (value, recalculated) in stated.iter().zip(posterior) {
+        let Ok(percent) = f64::from_str(value.text.trim_end_matches('%').trim_end()) else {
+            warnings.push(
+                value
+                    .error(
+                        "This posterior isn't a number, so it couldn't be checked",
+                        "Export the file again to write out the posterior",
+                    )
+                    .warning(),
+            );
+            continue;
+        };
+        if let Err(invalid) = check_posterior(percent, *recalculated) {
+            warnings.push(value.invalid(invalid).warning());
+        }
+    }
+    warnings
+}
+
+pub fn parse_markdown(content: &str) -> Result<Imported, ImportError> {
+    let mut hypotheses: Vec<Located> = Vec::new();
+    let mut prior_odds: Vec<f64> = Vec::new();
+    let mut evidence: Vec<String> = Vec::new();
+    let mut likelihoods: Vec<Vec<f64>> = Vec::new();
+    let mut active: Vec<bool> = Vec::new();
+    let mut planned: Vec<bool> = Vec::new();
+    let mut actions: Vec<String> = Vec::new();
+    let mut utilities: Vec<Vec<f64>> = Vec::new();
+    let mut prior_spread: Vec<Option<Spread>> = Vec::new();
+    let mut likelihood_spread: Vec<Vec<Option<Spread>>> = Vec::new();
+    // `None` where the ### header couldn't be read, so its values aren't either.
+    let mut kinds: Vec<Option<EvidenceKind>> = Vec::new();
+    // Checked against ## Prior once it's all read.
+    let mut evidence_blocks: Vec<Block> = Vec::new();
+    let mut action_blocks: Vec<Block> = Vec::new();
+    let mut posterior_block: Option<Block> = None;
+    let mut stated_posterior: Vec<Located> = Vec::new();
+
+    let mut diagnostics = Vec::new();
+    let lines: Vec<&str> = content.lines().collect();
+    let (version, skip) = front_matter(&lines, &mut diagnostics)?;
+    let mut current_section: &str = "";
+    let mut section_at: Option<Located> = None;
+
+    for (idx, line) in lines.iter().enumerate().skip(skip) {
+        let at = Located::line(idx + 1, line);
+        let hashes = line.len() - line.trim_start_matches('#').len();
+        if hashes == 2 {
+            let section = at.slice(hashes, line.len()).trim();
+            current_section = section.text;
+            section_at = Some(section);
+            continue;
+        }
+        if line.trim().is_empty() {
+            continue;
+        }
+        // A ### header, with the label as written, e.g. "~~Label~~".
+        let header = (hashes >= 3).then(|| {
+            let label = at.slice(hashes, line.len()).trim();
+            label.strip_suffix(':').unwrap_or(label).trim_end()
+        });
+
+        // Each line is read on its own, so one mistake doesn't hide the rest.
+        let result = (|| -> Result<(), Diagnostic> {
+            match current_section {
+                "Prior" => {
+                    let (hypothesis, value) = split_line(at)?;
+                    let earlier: Vec<&str> = hypotheses.iter().map(|h| h.text).collect();
+                    check_new_hypothesis(&earlier, hypothesis.text)
+                        .map_err(|invalid| hypothesis.invalid(invalid))?;
+                    hypotheses.push(hypothesis);
+                    let (value, spread) = split_spread(value, 1.0)?;
+                    let prior = parse_number(value, "A: 1")?;
+                    check_prior(prior).map_err(|invalid| value.invalid(invalid))?;
+                    prior_odds.push(prior);
+                    prior_spread.push(spread);
+                }
+                "Evidence" => {
+                    if let Some(label) = header {
+                        evidence_blocks.push(Block {
+                            header: label,
+                            names: Vec::new(),
+                        });
+                        likelihoods.push(Vec::new());
+                        likelihood_spread.push(Vec::new());
+                        // Inactive evidence is written struck through, ### ~~Label~~:,
+                        // planned evidence in italics, ### _Label_:, and other kinds
+                        // follow the label, see `split_kind`.
+                        let (label, kind) =
+                            match split_kind(label, version >= ESCAPED_NAMES_VERSION) {
+                                Ok((label, kind)) => (label, Some(kind)),
+                                Err(diagnostic) => {
+                                    diagnostics.push(diagnostic);
+                                    (label, None)
+                                }
+                            };
+                        kinds.push(kind);
+                        let (label, inactive, is_planned) =
+                            unmark_label(label.text, version >= ESCAPED_LABELS_VERSION);
+                        evidence.push(label);
+                        active.push(!inactive);
+                        planned.push(is_planned);
+                        return Ok(());
+                    }
+                    let Some(block) = evidence_blocks.last_mut() else {
+                        return Err(at.trim().error(
+                            "This likelihood comes before any piece of evidence",
+                            "Add a '### Evidence:' line above it",
+                        ));
+                    };
+                    let (hypothesis, value) = split_line(at)?;
+                    block.names.push(hypothesis);
+                    let (row, spreads) = (
+                        likelihoods.last_mut().unwrap(),
+                        likelihood_spread.last_mut().unwrap(),
+                    );
+                    match kinds.last_mut().unwrap() {
+                        None => {}
+                        Some(EvidenceKind::Continuous(continuous)) => {
+                            let distribution =
+                                Distribution::parse(value.text).map_err(|message| {
+                                    value.error(message, "Write a distribution like Normal(0, 1)")
+                                })?;
+                            continuous.distributions.push(distribution);
+                            row.push(0.0);
+                            spreads.push(None);
+                        }
+                        Some(EvidenceKind::Categorical(categorical)) => {
+                            let cells = value
+                                .split('|')
+                                .into_iter()
+                                .map(|cell| parse_likelihood(cell.trim()))
+                                .collect::<Result<Vec<f64>, Diagnostic>>()?;
+                            if cells.len() != categorical.outcomes.len() {
+                                return Err(value.error(
+                                    format!(
+                                        "Expected {} outcomes but found {}",
+                                        categorical.outcomes.len(),
+                                        cells.len()
+                                    ),
+                                    "Give one percentage per outcome, separated by |",
+                                ));
+                            }
+                            check_outcome_total(&cells)
+                                .map_err(|invalid| value.invalid(invalid))?;
+                            categorical.table.push(cells);
+                            row.push(0.0);
+                            spreads.push(None);
+                        }
+                        _ => {
+                            let (value, spread) = split_spread(value, 0.01)?;
+                            row.push(parse_likelihood(value)?);
+                            spreads.push(spread);
+                        }
+                    }
+                }
+                "Posterior" => {
+                    // The posterior is recalculated, and only compared with
+                    // this once the file has been read.
+                    let (hypothesis, value) = split_line(at)?;
+                    posterior_block
+                        .get_or_insert_with(|| Block {
+                            header: section_at.unwrap_or(at),
+                            names: Vec::new(),
+                        })
+                        .names
+                        .push(hypothesis);
+                    stated_posterior.push(value);
+                }
+                "Decision" => {
+                    if let Some(action) = header {
+                        actions.push(read_name(action.text, version >= ESCAPED_NAMES_VERSION));
+                        utilities.push(Vec::new());
+                        action_blocks.push(Block {
+                            header: action,
+                            names: Vec::new(),
+                        });
+                        return Ok(());
+                    }
+                    let (Some(row), Some(block)) = (utilities.last_mut(), action_blocks.last_mut())
+                    else {
+                        return Err(at.trim().error(
+                            "This utility comes before any action",
+                            "Add a '### Action:' line above it",
+                        ));
+                    };
+                    let (hypothesis, value) = split_line(at)?;
+                    block.names.push(hypothesis);
+                    let utility = parse_number(value, "A: 10")?;
+                    check_utility(utility).map_err(|invalid| value.invalid(invalid))?;
+                    row.push(utility);
+                }
+                // Expected utility, evidence strength and log-odds are all
+                // recalculated, and anything else isn't ours.
+                _ => {}
+            }
+            Ok(())
+        })();
+        if let Err(diagnostic) = result {
+            diagnostics.push(diagnostic);
+        }
+    }
+
+    if hypotheses.is_empty() {
+        diagnostics.push(Diagnostic::whole_file(
+            "There are no hypotheses",
+            "Add a '## Prior' section with a line per hypothesis, e.g. 'A: 1'",
+        ));
+    }
+    for block in evidence_blocks
+        .iter()
+        .chain(&action_blocks)
+        .chain(&posterior_block)
+    {
+        check_names(block, &hypotheses, &mut diagnostics);
+    }
+    if !diagnostics.is_empty() {
+        diagnostics.sort_by_key(|diagnostic| (diagnostic.line, diagnostic.column));
+        return Err(ImportError { diagnostics });
+    }
+
+    let mut data = BayesData {
+        hypotheses: hypotheses
+            .iter()
+            .map(|h| read_name(h.text, version >= ESCAPED_NAMES_VERSION))
+            .collect(),
+        prior_odds,
+        posterior_odds: Vec::new(),
+        evidence,
+        likelihoods,
+        active,
+        prior_spread,
+        likelihood_spread,
+        kinds: Vec::new(),
+        planned,
+        actions,
+        utilities,
+        entry_modes: Vec::new(),
+    };
+    // Every header pushed a kind, and a `None` was an error, so each row has one.
+    for (ev_idx, kind) in (0..data.evidence.len()).zip(kinds) {
+        if let Some(kind) = kind {
+            data.set_kind(ev_idx, kind);
+        }
+    }
+    data.posterior_odds = percentize(recalculate(
+        data.prior_odds.clone(),
+        data.effective_likelihoods(),
+    ));
+    let warnings = posterior_warnings(&stated_posterior, &data.posterior_odds);
+
+    Ok(Imported { data, warnings })
+}
+
+impl fmt::Display for BayesData {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "---\nformat: bayes-calc\nversion: {}\n---",
+            MARKDOWN_VERSION
+        )?;
+        let hypotheses: Vec<String> = self.hypotheses.iter().map(|h| escape_label(h)).collect();
+        writeln!(f, "\n## Prior")?;
+        for (idx, hypothesis) in hypotheses.iter().enumerate() {
+            write!(f, "{}: {}", hypothesis, self.prior_odds[idx])?;
+            match self.prior_spread(idx) {
+                Some(spread) => writeln!(f, " {}", spread.format(1.0, false))?,
+                None => writeln!(f)?,
+            }
+        }
+
+        write!(f, "\n## Evidence")?;
+        for (ev_idx, likelihood) in self.likelihoods.iter().enumerate() {
+            let suffix = match self.kind(ev_idx) {
+                EvidenceKind::Binary => String::new(),
+                EvidenceKind::Categorical(categorical) => {
+                    let names: Vec<String> = categorical
+                        .outcomes
+                        .iter()
+                        .enumerate()
+                        .map(|(idx, name)| {
+                            let name = escape_label(name);
+                            if idx == categorical.observed {
+                                format!("*{}*", name)
+                            } else {
+                                name
+                            }
+                        })
+                        .collect();
+                    format!(" {{{}}}", names.join(" | "))
+                }
+                EvidenceKind::Continuous(continuous) => format!(" {{= {}}}", continuous.observed),
+            };
+            writeln!(f, "\n### {}{}:", mark_label(self, ev_idx), suffix)?;
+            match self.kind(ev_idx) {
+                EvidenceKind::Binary => {}
+                EvidenceKind::Categorical(categorical) => {
+                    for (idx, hypothesis) in hypotheses.iter().enumerate() {
+                        let cells: Vec<String> = categorical.table[idx]
+                            .iter()
+                            .map(|p| format!("{}%", format_percent(*p)))
+                            .collect();
+                        writeln!(f, "{}: {}", hypothesis, cells.join(" | "))?;
+                    }
+                    continue;
+                }
+                EvidenceKind::Continuous(continuous) => {
+                    for (idx, hypothesis) in hypotheses.iter().enumerate() {
+                        writeln!(f, "{}: {}", hypothesis, continuous.distributions[idx])?;
+                    }
+                    continue;
+                }
+            }
+            for (idx, hypothesis) in hypotheses.iter().enumerate() {
+                write!(f, "{}: {}%", hypothesis, format_percent(likelihood[idx]))?;
+                match self.likelihood_spread(ev_idx, idx) {
+                    Some(spread) => writeln!(f, " {}", spread.format(0.01, true))?,
+                    None => writeln!(f)?,
+                }
+            }
+        }
+
+        writeln!(f, "\n## Posterior")?;
+        for (idx, hypothesis) in hypotheses.iter().enumerate() {
+            writeln!(f, "{}: {}", hypothesis, self.posterior_odds[idx])?;
+        }
+
+        if !self.actions.is_empty() {
+            write!(f, "\n## Decision")?;
+            for (action_idx, action) in self.actions.iter().enumerate() {
+                writeln!(f, "\n### {}:", escape_label(action))?;
+                for (idx, hypothesis) in hypotheses.iter().enumerate() {
+                    writeln!(f, "{}: {}", hypothesis, self.utilities[action_idx][idx])?;
+                }
+            }
+            if let Some(decision) = decide(&self.posterior_odds, &self.utilities) {
+                writeln!(f, "\n## Expected utility")?;
+                for (action_idx, action) in self.actions.iter().enumerate() {
+                    writeln!(
+                        f,
+                        "{}: {}",
+                        escape_label(action),
+                        decision.expected[action_idx]
+                    )?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// The markdown `Display` writes, followed by how strong each piece of
+/// evidence is and the posterior log-odds, in `unit`. Those sections are only
+/// for reading and are ignored by `parse_markdown`.
+pub fn to_markdown(data: &BayesData, unit: StrengthUnit) -> String {
+    let mut markdown = data.to_string();
+    markdown += &format!("\n## Evidence strength ({})\n", unit.name());
+    for (ev_idx, label) in data.evidence.iter().enumerate() {
+        let bayes_factors = log_odds_in_db(data.likelihoods[ev_idx].clone());
+        let strength = match strongest(&bayes_factors) {
+            Some(hyp_idx) => format!(
+                "{} for {}",
+                unit.format(bayes_factors[hyp_idx]),
+                escape_label(&data.hypotheses[hyp_idx])
+            ),
+            None => unit.format(0.0),
+        };
+        let note = if data.counts(ev_idx) {
+            ""
+        } else {
+            " (not counted)"
+        };
+        markdown += &format!("{}: {}{}\n", escape_label(label), strength, note);
+    }
+    markdown += &format!("\n## Posterior log-odds ({})\n", unit.name());
+    for (hypothesis, db) in data
+        .hypotheses
+        .iter()
+        .zip(log_odds_in_db(data.posterior_odds.clone()))
+    {
+        markdown += &format!("{}: {}\n", escape_label(hypothesis), unit.format(db));
+    }
+    markdown
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn example() -> BayesData {
+        let mut data = BayesData {
+            hypotheses: vec!["Rain".to_string(), "No rain: dry".to_string()],
+            prior_odds: vec![1.0, 3.5],
+            evidence: vec!["Clouds".to_string(), "Forecast".to_string()],
+            likelihoods: vec![vec![0.9, 0.35], vec![0.7, 0.125]],
+            active: vec![true, false],
+            actions: vec!["Umbrella".to_string()],
+            utilities: vec![vec![5.0, -1.0]],
+            ..BayesData::default()
+        };
+        data.posterior_odds = percentize(recalculate(
+            data.prior_odds.clone(),
+            data.effective_likelihoods(),
+        ));
+        data
+    }
+
+    #[test]
+    fn display_round_trips() {
+        let data = example();
+        let imported = parse_markdown(&data.to_string()).unwrap();
+        assert!(imported.warnings.is_empty());
+        let back = imported.data;
+        assert_eq!(back.hypotheses, data.hypotheses);
+        assert_eq!(back.prior_odds, data.prior_odds);
+        assert_eq!(back.posterior_odds, data.posterior_odds);
+        assert_eq!(back.evidence, data.evidence);
+        assert_eq!(back.likelihoods, data.likelihoods);
+        assert_eq!(back.active, data.active);
+        assert_eq!(back.actions, data.actions);
+        assert_eq!(back.utilities, data.utilities);
+        // Writing what was read gives the same file.
+        assert_eq!(back.to_string(), data.to_string());
+    }
+
+    #[test]
+    fn to_markdown_round_trips() {
+        let data = example();
+        let back = parse_markdown(&to_markdown(&data, StrengthUnit::Bits))
+            .unwrap()
+            .data;
+        assert_eq!(back.to_string(), data.to_string());
+    }
+
+    #[test]
+    fn labels_that_look_like_markup_round_trip() {
+        let labels = [
+            "_Draft_",
+            "~~Old~~",
+            "Dice {1 | *2*}",
+            "Reading {= 5}",
+            "#1 suspect",
+            "back\\slash\\",
+            "snake_case_",
+        ];
+        let mut data = example();
+        data.evidence = labels.iter().map(|label| label.to_string()).collect();
+        data.likelihoods = vec![vec![0.5, 0.25]; labels.len()];
+        data.active = vec![true; labels.len()];
+        data.active[1] = false;
+        data.planned = vec![false; labels.len()];
+        data.planned[0] = true;
+        data.posterior_odds = percentize(recalculate(
+            data.prior_odds.clone(),
+            data.effective_likelihoods(),
+        ));
+        let back = parse_markdown(&data.to_string()).unwrap().data;
+        assert_eq!(back.evidence, data.evidence);
+        assert_eq!(back.active, data.active);
+        assert_eq!(back.planned, data.planned);
+        assert!((0..labels.len()).all(|ev_idx| back.kind(ev_idx) == &EvidenceKind::Binary));
+    }
+
+    #[test]
+    fn outcome_names_that_look_like_markup_round_trip() {
+        let outcomes = ["a | b", "*starred*", "back\\", "{x}"];
+        let mut data = example();
+        data.evidence = vec!["Dice".to_string()];
+        data.likelihoods = vec![vec![0.25, 0.25]];
+        data.active = vec![true];
+        for observed in 0..outcomes.len() {
+            data.set_kind(
+                0,
+                EvidenceKind::Categorical(Categorical {
+                    outcomes: outcomes.iter().map(|name| name.to_string()).collect(),
+                    table: vec![vec![0.25; 4]; 2],
+                    observed,
+                }),
+            );
+            let back = parse_markdown(&data.to_string()).unwrap().data;
+            assert_eq!(back.evidence, data.evidence);
+            assert_eq!(back.kind(0), data.kind(0));
+        }
+    }
+
+    #[test]
+    fn hypothesis_and_action_names_that_look_like_markup_round_trip() {
+        let mut data = example();
+        data.hypotheses = vec!["## Evidence".to_string(), "back\\".to_string()];
+        data.actions = vec!["_Wait_ | see".to_string(), "#2:".to_string()];
+        data.utilities = vec![vec![1.0, 0.0], vec![0.0, 1.0]];
+        let back = parse_markdown(&data.to_string()).unwrap().data;
+        assert_eq!(back.hypotheses, data.hypotheses);
+        assert_eq!(back.actions, data.actions);
+        assert_eq!(back.utilities, data.utilities);
+        assert_eq!(back.evidence, data.evidence);
+    }
+
+    #[test]
+    fn names_before_version_4_are_not_unescaped() {
+        let text = "---\nversion: 3\n---\n## Prior\nA: 1\n## Evidence\n\
+                    ### Path {C:\\temp | *D:\\*}:\nA: 50% | 50%\n";
+        let data = parse_markdown(text).unwrap().data;
+        let EvidenceKind::Categorical(categorical) = data.kind(0) else {
+            panic!("expected a categorical kind");
+        };
+        assert_eq!(categorical.outcomes, vec!["C:\\temp", "D:\\"]);
+        assert_eq!(categorical.observed, 1);
+        let text = text
+            .replace("A: 1", "C:\\a_b: 1")
+            .replace("A: 50%", "C:\\a_b: 50%");
+        let data = parse_markdown(&text).unwrap().data;
+        assert_eq!(data.hypotheses, vec!["C:\\a_b"]);
+    }
+
+    #[test]
+    fn categorical_rows_must_sum_to_100_percent() {
+        let text = "## Prior\nA: 1\nB: 1\n## Evidence\n### Dice {low | *high*}:\n\
+                    A: 33.3% | 66.7%\nB: 50% | 30%\n";
+        let err = parse_markdown(text).unwrap_err();
+        assert_eq!(err.diagnostics.len(), 1);
+        assert_eq!((err.diagnostics[0].line, err.diagnostics[0].column), (7, 4));
+        assert!(err.diagnostics[0].message.contains("80.0%"));
+
+        let rounded = text.replace("50% | 30%", "33.3% | 66.6%");
+        let data = parse_markdown(&rounded).unwrap().data;
+        assert_eq!(data.likelihoods[0], vec![0.667, 0.666]);
+    }
+
+    #[test]
+    fn awkward_floats_round_trip_exactly() {
+        let awkward = [0.1, 1e-300, 1.0 / 3.0, 0.07, 2.0 / 3.0, 5e-324, 1.0, 0.0];
+        let mut data = example();
+        data.prior_odds = vec![0.1, 1.0 / 3.0];
+        data.evidence = (0..awkward.len())
+            .map(|ev_idx| ev_idx.to_string())
+            .collect();
+        data.likelihoods = awkward.iter().map(|p| vec![*p, 1.0 - p]).collect();
+        data.active = vec![true; awkward.len()];
+        data.utilities = vec![vec![0.1, 1e-300]];
+        let back = parse_markdown(&data.to_string()).unwrap().data;
+        assert_eq!(back.prior_odds, data.prior_odds);
+        assert_eq!(back.likelihoods, data.likelihoods);
+        assert_eq!(back.utilities, data.utilities);
+    }
+
+    fn errors(text: &str) -> Vec<(usize, String)> {
+        parse_markdown(text)
+            .unwrap_err()
+            .diagnostics
+            .into_iter()
+            .map(|diagnostic| (diagnostic.line, diagnostic.message))
+            .collect()
+    }
+
+    #[test]
+    fn places_errors_at_the_text_at_fault() {
+        // Columns count characters, so the é before the x counts once.
+        let text = "## Prior\nA: 1\nBé:  x\n## Evidence\n### E {a | b}:\nA: 50%\nBé: 50%\n\
+                    ### F {*a* | b}:\nA: 50% | 5O%\nBé: 50% | 50%\n\
+                    ### G:\nA: 50% [40%, 6O%]\nBé: 50%\n";
+        assert_eq!(
+            parse_markdown(text).unwrap_err().positions(),
+            vec![
+                (3, 6, "x".to_string()),
+                (5, 8, "a | b".to_string()),
+                (9, 10, "5O%".to_string()),
+                (12, 8, "[40%, 6O%]".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn rejects_newer_versions() {
+        let text = "---\nformat: bayes-calc\nversion: 99\n---\n## Prior\nA: 1\n";
+        assert_eq!(
+            errors(text),
+            vec![(
+                3,
+                "This file is format version 99, but only versions up to 4 can be read".to_string()
+            )]
+        );
+        let unreadable = "---\nversion: two\n---\n## Prior\nA: 1\n";
+        assert_eq!(errors(unreadable)[0].0, 2);
+    }
+
+    #[test]
+    fn rejects_duplicate_and_mismatched_names() {
+        let duplicate = "## Prior\nA: 1\nA: 2\n";
+        assert_eq!(
+            errors(duplicate),
+            vec![(3, "This hypothesis is already listed".to_string())]
+        );
+        let renamed = "## Prior\nA: 1\nB: 1\n## Evidence\n### E:\nA: 50%\nC: 50%\n";
+        assert_eq!(
+            errors(renamed),
+            vec![(7, "Expected hypothesis 'B' here".to_string())]
+        );
+        let extra = "## Prior\nA: 1\n## Decision\n### Go:\nA: 1\nB: 2\n";
+        assert_eq!(
+            errors(extra),
+            vec![(
+                6,
+                "This isn't one of the hypotheses in ## Prior".to_string()
+            )]
+        );
+    }
+
+    #[test]
+    fn rejects_ragged_tables() {
+        let short = "## Prior\nA: 1\nB: 1\n## Evidence\n### E:\nA: 50%\n";
+        assert_eq!(
+            errors(short),
+            vec![(
+                5,
+                "Only 1 of the 2 hypotheses are listed under this".to_string()
+            )]
+        );
+        let outcomes = "## Prior\nA: 1\n## Evidence\n### E {x | *y*}:\nA: 20% | 30% | 50%\n";
+        assert_eq!(
+            errors(outcomes),
+            vec![(5, "Expected 2 outcomes but found 3".to_string())]
+        );
+    }
+
+    #[test]
+    fn kinds_stay_with_their_rows() {
+        let text = "## Prior\nA: 1\nB: 1\n## Evidence\n### First:\nA: 50%\nB: 50%\n\
+                    ### Second {low | *high*}:\nA: 20% | 80%\nB: 60% | 40%\n\
+                    ### Third:\nA: 10%\nB: 20%\n";
+        let data = parse_markdown(text).unwrap().data;
+        assert_eq!(data.kind(0), &EvidenceKind::Binary);
+        assert!(data.categorical(1).is_some());
+        assert_eq!(data.kind(2), &EvidenceKind::Binary);
+        assert_eq!(data.likelihoods[1], vec![0.8, 0.4]);
+        assert_eq!(data.likelihoods[2], vec![0.1, 0.2]);
+    }
+
+    #[test]
+    fn stale_posteriors_are_warned_about_on_their_lines() {
+        // The priors give 25% and 75%.
+        let text = "## Prior\nA: 1\nB: 3\n## Posterior\nA: 30%\nB: 75%\n";
+        let imported = parse_markdown(text).unwrap();
+        assert_eq!(imported.data.posterior_odds, vec![25.0, 75.0]);
+        let warnings: Vec<(usize, String)> = imported
+            .warnings
+            .into_iter()
+            .map(|warning| (warning.line, warning.text))
+            .collect();
+        assert_eq!(warnings, vec![(5, "30%".to_string())]);
+        let missing = "## Prior\nA: 1\nB: 3\n## Posterior\nA: 25%\n";
+        assert_eq!(
+            errors(missing),
+            vec![(
+                4,
+                "Only 1 of the 2 hypotheses are listed under this".to_string()
+            )]
+        );
+    }
+
+    #[test]
+    fn labels_before_version_3_are_not_unescaped() {
+        let text = "---\nversion: 2\n---\n## Prior\nA: 1\n## Evidence\n### C:\\temp:\nA: 50%\n";
+        let data = parse_markdown(text).unwrap().data;
+        assert_eq!(data.evidence, vec!["C:\\temp"]);
+    }
+}
@@ -0,0 +1,234 @@
+use crate::entry::EntryMode;
+use crate::evidence::EvidenceKind;
+use crate::uncertainty::Spread;
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct BayesData {
+    pub hypotheses: Vec<String>,
+    pub prior_odds: Vec<f64>,
+    /// Always recalculated, so it may be left out, e.g. of imported JSON.
+    #[serde(default)]
+    pub posterior_odds: Vec<f64>,
+    pub evidence: Vec<String>,
+    pub likelihoods: Vec<Vec<f64>>,
+    /// Whether each evidence row counts towards the posterior. Missing entries
+    /// (e.g. from data saved before this existed) count as active.
+    #[serde(default)]
+    pub active: Vec<bool>,
+    /// Optional uncertainty on each prior, indexed like `prior_odds`.
+    #[serde(default)]
+    pub prior_spread: Vec<Option<Spread>>,
+    /// Optional uncertainty on each likelihood, indexed like `likelihoods`.
+    #[serde(default)]
+    pub likelihood_spread: Vec<Vec<Option<Spread>>>,
+    /// The kind of each evidence row. Missing entries are binary.
+    #[serde(default)]
+    pub kinds: Vec<EvidenceKind>,
+    /// Whether each evidence row is planned rather than observed. Planned rows
+    /// never count towards the posterior. Missing entries are observed.
+    #[serde(default)]
+    pub planned: Vec<bool>,
+    /// Optional decision table: the actions being chosen between and, for
+    /// each, its utility under every hypothesis.
+    #[serde(default)]
+    pub actions: Vec<String>,
+    #[serde(default)]
+    pub utilities: Vec<Vec<f64>>,
+    /// How each evidence row's likelihoods are entered. Missing entries are
+    /// percentages.
+    #[serde(default)]
+    pub entry_modes: Vec<EntryMode>,
+}
+
+impl Default for BayesData {
+    /// The two-hypothesis, one-evidence analysis a new session starts with.
+    fn default() -> Self {
+        BayesData {
+            hypotheses: vec!["Hypothesis A".to_string(), "Hypothesis B".to_string()],
+            prior_odds: vec![50.0, 50.0],
+            posterior_odds: vec![50.0, 50.0],
+            evidence: vec!["Evidence 1".to_string()],
+            likelihoods: vec![vec![0.5, 0.5]],
+            active: vec![true],
+            prior_spread: Vec::new(),
+            likelihood_spread: Vec::new(),
+            kinds: Vec::new(),
+            planned: Vec::new(),
+            actions: Vec::new(),
+            utilities: Vec::new(),
+            entry_modes: Vec::new(),
+        }
+    }
+}
+
+impl BayesData {
+    /// Appends a hypothesis with even odds and a 50% likelihood for every
+    /// piece of evidence.
+    pub fn add_hypothesis(&mut self, name: String, prior: f64) {
+        self.hypotheses.push(name);
+        self.prior_odds.push(prior);
+        self.posterior_odds.push(prior);
+        for row in self.likelihoods.iter_mut() {
+            row.push(0.5);
+        }
+        self.add_hypothesis_to_kinds();
+        for row in self.utilities.iter_mut() {
+            row.push(0.0);
+        }
+    }
+
+    pub fn remove_hypothesis(&mut self, hyp_idx: usize) {
+        self.hypotheses.remove(hyp_idx);
+        self.prior_odds.remove(hyp_idx);
+        self.posterior_odds.remove(hyp_idx);
+        for row in self.likelihoods.iter_mut() {
+            row.remove(hyp_idx);
+        }
+        if hyp_idx < self.prior_spread.len() {
+            self.prior_spread.remove(hyp_idx);
+        }
+        for row in self.likelihood_spread.iter_mut() {
+            if hyp_idx < row.len() {
+                row.remove(hyp_idx);
+            }
+        }
+        self.remove_hypothesis_from_kinds(hyp_idx);
+        for row in self.utilities.iter_mut() {
+            row.remove(hyp_idx);
+        }
+        self.remove_hypothesis_from_entry_modes(hyp_idx);
+    }
+
+    pub fn add_evidence(&mut self, label: String) {
+        self.evidence.push(label);
+        self.likelihoods.push(vec![0.5; self.hypotheses.len()]);
+        self.set_active(self.evidence.len() - 1, true);
+    }
+
+    pub fn remove_evidence(&mut self, ev_idx: usize) {
+        self.evidence.remove(ev_idx);
+        self.likelihoods.remove(ev_idx);
+        if ev_idx < self.active.len() {
+            self.active.remove(ev_idx);
+        }
+        if ev_idx < self.likelihood_spread.len() {
+            self.likelihood_spread.remove(ev_idx);
+        }
+        if ev_idx < self.kinds.len() {
+            self.kinds.remove(ev_idx);
+        }
+        if ev_idx < self.planned.len() {
+            self.planned.remove(ev_idx);
+        }
+        if ev_idx < self.entry_modes.len() {
+            self.entry_modes.remove(ev_idx);
+        }
+    }
+
+    /// Appends an action with a utility of 0 under every hypothesis.
+    pub fn add_action(&mut self, name: String) {
+        self.actions.push(name);
+        self.utilities.push(vec![0.0; self.hypotheses.len()]);
+    }
+
+    pub fn remove_action(&mut self, action_idx: usize) {
+        self.actions.remove(action_idx);
+        self.utilities.remove(action_idx);
+    }
+
+    pub fn is_active(&self, ev_idx: usize) -> bool {
+        self.active.get(ev_idx).copied().unwrap_or(true)
+    }
+
+    pub fn set_active(&mut self, ev_idx: usize, active: bool) {
+        if self.active.len() < self.evidence.len() {
+            self.active.resize(self.evidence.len(), true);
+        }
+        self.active[ev_idx] = active;
+    }
+
+    pub fn is_planned(&self, ev_idx: usize) -> bool {
+        self.planned.get(ev_idx).copied().unwrap_or(false)
+    }
+
+    pub fn set_planned(&mut self, ev_idx: usize, planned: bool) {
+        if self.planned.len() < self.evidence.len() {
+            self.planned.resize(self.evidence.len(), false);
+        }
+        self.planned[ev_idx] = planned;
+    }
+
+    /// Whether an evidence row is part of the posterior: active and observed.
+    pub fn counts(&self, ev_idx: usize) -> bool {
+        self.is_active(ev_idx) && !self.is_planned(ev_idx)
+    }
+
+    pub fn prior_spread(&self, hyp_idx: usize) -> Option<Spread> {
+        self.prior_spread.get(hyp_idx).copied().flatten()
+    }
+
+    pub fn likelihood_spread(&self, ev_idx: usize, hyp_idx: usize) -> Option<Spread> {
+        self.likelihood_spread
+            .get(ev_idx)
+            .and_then(|row| row.get(hyp_idx))
+            .copied()
+            .flatten()
+    }
+
+    pub fn set_prior_spread(&mut self, hyp_idx: usize, spread: Option<Spread>) {
+        if self.prior_spread.len() < self.hypotheses.len() {
+            self.prior_spread.resize(self.hypotheses.len(), None);
+        }
+        self.prior_spread[hyp_idx] = spread;
+    }
+
+    pub fn set_likelihood_spread(&mut self, ev_idx: usize, hyp_idx: usize, spread: Option<Spread>) {
+        if self.likelihood_spread.len() < self.evidence.len() {
+            self.likelihood_spread
+                .resize(self.evidence.len(), Vec::new());
+        }
+        let row = &mut self.likelihood_spread[ev_idx];
+        if row.len() < self.hypotheses.len() {
+            row.resize(self.hypotheses.len(), None);
+        }
+        row[hyp_idx] = spread;
+    }
+
+    pub fn has_spread(&self) -> bool {
+        self.prior_spread.iter().any(Option::is_some)
+            || self
+                .likelihood_spread
+                .iter()
+                .enumerate()
+                .any(|(ev_idx, row)| self.counts(ev_idx) && row.iter().any(Option::is_some))
+    }
+
+    /// The likelihoods with every inactive or planned row replaced by 1s, so it
+    /// has no effect on `recalculate`/`recalculate_to` but keeps the row indices.
+    pub fn effective_likelihoods(&self) -> Vec<Vec<f64>> {
+        self.likelihoods
+            .iter()
+            .enumerate()
+            .map(|(ev_idx, row)| {
+                if self.counts(ev_idx) {
+                    row.clone()
+                } else {
+                    vec![1.0; row.len()]
+                }
+            })
+            .collect()
+    }
+}
+
+/// Spreadsheet-style letters for the hypothesis at `idx`: A..Z, then AA, AB, ...
+pub fn hypothesis_letters(idx: usize) -> String {
+    let mut letters = Vec::new();
+    let mut n = idx + 1;
+    while n > 0 {
+        n -= 1;
+        letters.push((b'A' + (n % 26) as u8) as char);
+        n /= 26;
+    }
+    letters.iter().rev().collect()
+}
@@ -0,0 +1,286 @@
+/// What a number typed into an input means.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Quantity {
+    /// A plain number, possibly the result of some arithmetic.
+    Number(f64),
+    /// A probability between 0 and 1, from a percentage, odds or a frequency.
+    Probability(f64),
+}
+
+impl Quantity {
+    /// The value for an input that holds percentages if `percent`, or plain
+    /// numbers otherwise. Plain numbers are taken as they are either way.
+    pub fn value(&self, percent: bool) -> f64 {
+        match self {
+            Quantity::Number(x) => *x,
+            Quantity::Probability(p) if percent => 100.0 * p,
+            Quantity::Probability(p) => *p,
+        }
+    }
+}
+
+struct Parser<'a> {
+    chars: Vec<char>,
+    pos: usize,
+    input: &'a str,
+}
+
+impl<'a> Parser<'a> {
+    fn new(input: &'a str) -> Self {
+        Parser {
+            chars: input.chars().collect(),
+            pos: 0,
+            input,
+        }
+    }
+
+    fn skip_spaces(&mut self) {
+        while self.chars.get(self.pos).is_some_and(|c| c.is_whitespace()) {
+            self.pos += 1;
+        }
+    }
+
+    fn peek(&mut self) -> Option<char> {
+        self.skip_spaces();
+        self.chars.get(self.pos).copied()
+    }
+
+    fn eat(&mut self, c: char) -> bool {
+        if self.peek() == Some(c) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect_end(&mut self) -> Result<(), String> {
+        match self.peek() {
+            None => Ok(()),
+            Some(c) => Err(format!("Unexpected '{}' in \"{}\"", c, self.input.trim())),
+        }
+    }
+
+    fn number(&mut self) -> Result<f64, String> {
+        self.skip_spaces();
+        let start = self.pos;
+        while self
+            .chars
+            .get(self.pos)
+            .is_some_and(|c| c.is_ascii_digit() || *c == '.')
+        {
+            self.pos += 1;
+        }
+        // Scientific notation, e.g. 1e-3.
+        if self.pos > start && matches!(self.chars.get(self.pos), Some('e' | 'E')) {
+            let mark = self.pos;
+            self.pos += 1;
+            if matches!(self.chars.get(self.pos), Some('+' | '-')) {
+                self.pos += 1;
+            }
+            let digits = self.pos;
+            while self.chars.get(self.pos).is_some_and(char::is_ascii_digit) {
+                self.pos += 1;
+            }
+            if self.pos == digits {
+                self.pos = mark;
+            }
+        }
+        let text: String = self.chars[start..self.pos].iter().collect();
+        match text.parse::<f64>() {
+            Ok(x) => Ok(x),
+            Err(_) if text.is_empty() => match self.chars.get(self.pos) {
+                Some(c) => Err(format!("Expected a number before '{}'", c)),
+                None => Err("Expected a number".to_string()),
+            },
+            Err(_) => Err(format!("\"{}\" isn't a number", text)),
+        }
+    }
+
+    fn factor(&mut self) -> Result<f64, String> {
+        if self.eat('-') {
+            return Ok(-self.factor()?);
+        }
+        if self.eat('+') {
+            return self.factor();
+        }
+        if self.eat('(') {
+            let x = self.expr()?;
+            if !self.eat(')') {
+                return Err("Missing ')'".to_string());
+            }
+            return Ok(x);
+        }
+        self.number()
+    }
+
+    fn term(&mut self) -> Result<f64, String> {
+        let mut x = self.factor()?;
+        loop {
+            if self.eat('*') || self.eat('×') {
+                x *= self.factor()?;
+            } else if self.eat('/') || self.eat('÷') {
+                let divisor = self.factor()?;
+                if divisor == 0.0 {
+                    return Err("Division by zero".to_string());
+                }
+                x /= divisor;
+            } else {
+                return Ok(x);
+            }
+        }
+    }
+
+    fn expr(&mut self) -> Result<f64, String> {
+        let mut x = self.term()?;
+        loop {
+            if self.eat('+') {
+                x += self.term()?;
+            } else if self.eat('-') {
+                x -= self.term()?;
+            } else {
+                return Ok(x);
+            }
+        }
+    }
+
+    /// A whole input that is just arithmetic.
+    fn arithmetic(mut self) -> Result<f64, String> {
+        let x = self.expr()?;
+        self.expect_end()?;
+        Ok(x)
+    }
+}
+
+fn arithmetic(input: &str) -> Result<f64, String> {
+    Parser::new(input).arithmetic()
+}
+
+fn probability(p: f64) -> Result<Quantity, String> {
+    if (0.0..=1.0).contains(&p) {
+        Ok(Quantity::Probability(p))
+    } else {
+        Err(format!("{} isn't a probability between 0 and 1", p))
+    }
+}
+
+/// Odds `a:b` in favour, as a probability.
+fn odds(a: f64, b: f64) -> Result<Quantity, String> {
+    if a < 0.0 || b < 0.0 || a + b == 0.0 {
+        return Err("Odds need two non-negative numbers, not both 0".to_string());
+    }
+    probability(a / (a + b))
+}
+
+/// Bookmaker odds after the word "odds": fractional `5/2` (stake 2 to win 5),
+/// decimal `3.5` (total return per unit staked) or American `+150`/`-200`.
+fn betting_odds(spec: &str) -> Result<Quantity, String> {
+    let spec = spec.trim();
+    if let Some((win, stake)) = spec.split_once('/') {
+        let (win, stake) = (arithmetic(win)?, arithmetic(stake)?);
+        return odds(stake, win);
+    }
+    if let Some(american) = spec.strip_prefix('+') {
+        let x = arithmetic(american)?;
+        if x < 100.0 {
+            return Err("American odds are +100 or more, or -100 or less".to_string());
+        }
+        return probability(100.0 / (x + 100.0));
+    }
+    if let Some(american) = spec.strip_prefix('-') {
+        let x = arithmetic(american)?;
+        if x < 100.0 {
+            return Err("American odds are +100 or more, or -100 or less".to_string());
+        }
+        return probability(x / (x + 100.0));
+    }
+    let decimal = arithmetic(spec)?;
+    if decimal < 1.0 {
+        return Err("Decimal odds are 1 or more".to_string());
+    }
+    probability(1.0 / decimal)
+}
+
+/// Reads a number in any of the forms people write chances in:
+///
+/// - plain numbers and scientific notation: `12.5`, `1e-3`
+/// - arithmetic with `+ - * /` and brackets: `0.2*0.5`, `100/3`
+/// - percentages: `12.5%`
+/// - fractions of one: `1/3`
+/// - odds in favour: `3:1`
+/// - frequencies: `1 in 1000`
+/// - betting odds after the word "odds": `odds 5/2`, `odds 3.5`, `odds +150`
+pub fn parse_quantity(input: &str) -> Result<Quantity, String> {
+    let text = input.trim();
+    if text.is_empty() {
+        return Err("Enter a number".to_string());
+    }
+    let lower = text.to_lowercase();
+    if let Some(spec) = lower.strip_prefix("odds") {
+        return betting_odds(spec);
+    }
+    if let Some(percent) = text.strip_suffix('%') {
+        return Ok(Quantity::Probability(arithmetic(percent)? / 100.0));
+    }
+    if let Some((a, b)) = text.split_once(':') {
+        return odds(arithmetic(a)?, arithmetic(b)?);
+    }
+    // A lone fraction no bigger than 1 is a chance, like `1 in 3`; anything
+    // else with a `/` is arithmetic.
+    if let Some((a, b)) = text.split_once('/') {
+        if let (Ok(a), Ok(b)) = (a.trim().parse::<f64>(), b.trim().parse::<f64>()) {
+            if b.is_finite() && b > 0.0 && (0.0..=b).contains(&a) {
+                return Ok(Quantity::Probability(a / b));
+            }
+        }
+    }
+    if let Some((count, total)) = lower.split_once(" in ") {
+        let (count, total) = (arithmetic(count)?, arithmetic(total)?);
+        if total <= 0.0 {
+            return Err("\"N in M\" needs M above 0".to_string());
+        }
+        return probability(count / total);
+    }
+    arithmetic(text).map(Quantity::Number)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn value(input: &str, percent: bool) -> f64 {
+        parse_quantity(input).unwrap().value(percent)
+    }
+
+    #[test]
+    fn chances_are_percentages_in_percent_inputs() {
+        assert_eq!(value("12.5%", true), 12.5);
+        assert_eq!(value("3:1", true), 75.0);
+        assert_eq!(value("1 in 4", true), 25.0);
+        assert_eq!(value("odds 3/1", true), 25.0);
+        assert_eq!(value("1/4", true), 25.0);
+        assert_eq!(value("3:1", false), 0.75);
+        assert_eq!(value("1/4", false), 0.25);
+    }
+
+    #[test]
+    fn arithmetic_is_in_the_inputs_own_units() {
+        assert_eq!(value("100/4", true), 25.0);
+        assert_eq!(value("5/4", true), 1.25);
+        assert_eq!(value("(1)/4", true), 0.25);
+        assert!((value("0.2*0.5", false) - 0.1).abs() < 1e-12);
+        assert_eq!(value("-(2 + 3) * 2", false), -10.0);
+        assert_eq!(value("1e-3", false), 0.001);
+    }
+
+    #[test]
+    fn rejects_what_it_cannot_read() {
+        assert!(parse_quantity("").is_err());
+        assert!(parse_quantity("-").is_err());
+        assert!(parse_quantity("1/0").is_err());
+        assert!(parse_quantity("(1").is_err());
+        assert!(parse_quantity("5 in 0").is_err());
+        assert!(parse_quantity("3 in 2").is_err());
+        assert!(parse_quantity("odds 0.5").is_err());
+    }
+}
@@ -0,0 +1,153 @@
+use crate::calc::{percentize, recalculate};
+use crate::model::BayesData;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Input {
+    Prior(usize),
+    /// Evidence index, hypothesis index.
+    Likelihood(usize, usize),
+}
+
+/// How the posterior (in percent) responds to nudging one input down and up.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Sensitivity {
+    pub input: Input,
+    pub low: Vec<f64>,
+    pub high: Vec<f64>,
+    /// Central-difference estimate of d(posterior %)/d(input), per hypothesis.
+    pub derivative: Vec<f64>,
+    /// The largest change in any hypothesis's posterior between `low` and `high`.
+    pub swing: f64,
+}
+
+fn posterior(prior: Vec<f64>, likelihoods: Vec<Vec<f64>>) -> Vec<f64> {
+    percentize(recalculate(prior, likelihoods))
+}
+
+fn sensitivity(input: Input, step: (f64, f64), low: Vec<f64>, high: Vec<f64>) -> Sensitivity {
+    let width = step.1 - step.0;
+    let derivative = low
+        .iter()
+        .zip(&high)
+        .map(|(l, h)| if width > 0.0 { (h - l) / width } else { 0.0 })
+        .collect();
+    let swing = low
+        .iter()
+        .zip(&high)
+        .map(|(l, h)| (h - l).abs())
+        .fold(0.0, f64::max);
+    Sensitivity {
+        input,
+        low,
+        high,
+        derivative,
+        swing,
+    }
+}
+
+/// Moves every prior by `delta` of the total prior odds and every active
+/// likelihood by `delta` (clamped to [0, 1]) in each direction, re-running
+/// `recalculate` each time. Sorted with the most influential input first.
+pub fn sensitivities(data: &BayesData, delta: f64) -> Vec<Sensitivity> {
+    let likelihoods = data.effective_likelihoods();
+    let prior_step = delta * data.prior_odds.iter().sum::<f64>();
+    let mut results = Vec::new();
+
+    for hyp_idx in 0..data.prior_odds.len() {
+        let value = data.prior_odds[hyp_idx];
+        let (down, up) = ((value - prior_step).max(0.0), value + prior_step);
+        let mut prior = data.prior_odds.clone();
+        prior[hyp_idx] = down;
+        let low = posterior(prior.clone(), likelihoods.clone());
+        prior[hyp_idx] = up;
+        let high = posterior(prior, likelihoods.clone());
+        results.push(sensitivity(Input::Prior(hyp_idx), (down, up), low, high));
+    }
+
+    for (ev_idx, row) in data.likelihoods.iter().enumerate() {
+        if !data.counts(ev_idx) {
+            continue;
+        }
+        for (hyp_idx, value) in row.iter().enumerate() {
+            let (down, up) = ((value - delta).max(0.0), (value + delta).min(1.0));
+            let mut nudged = likelihoods.clone();
+            nudged[ev_idx][hyp_idx] = down;
+            let low = posterior(data.prior_odds.clone(), nudged.clone());
+            nudged[ev_idx][hyp_idx] = up;
+            let high = posterior(data.prior_odds.clone(), nudged);
+            results.push(sensitivity(
+                Input::Likelihood(ev_idx, hyp_idx),
+                (down, up),
+                low,
+                high,
+            ));
+        }
+    }
+
+    results.sort_by(|a, b| b.swing.total_cmp(&a.swing));
+    results
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(actual: &[f64], expected: &[f64]) {
+        assert_eq!(actual.len(), expected.len());
+        for (a, e) in actual.iter().zip(expected) {
+            assert!((a - e).abs() < 1e-9, "{:?} vs {:?}", actual, expected);
+        }
+    }
+
+    fn example() -> BayesData {
+        BayesData {
+            hypotheses: vec!["A".to_string(), "B".to_string()],
+            prior_odds: vec![1.0, 1.0],
+            evidence: vec!["E".to_string(), "Off".to_string()],
+            likelihoods: vec![vec![0.6, 0.2], vec![0.95, 0.5]],
+            active: vec![true, false],
+            ..BayesData::default()
+        }
+    }
+
+    #[test]
+    fn sweeps_each_input_both_ways() {
+        let results = sensitivities(&example(), 0.1);
+        let inputs: Vec<Input> = results.iter().map(|s| s.input).collect();
+        // Inactive evidence isn't swept, and the biggest swing comes first.
+        assert_eq!(
+            inputs,
+            vec![
+                Input::Likelihood(0, 1),
+                Input::Prior(0),
+                Input::Prior(1),
+                Input::Likelihood(0, 0),
+            ]
+        );
+        // B's likelihood from 10% to 30%: A goes from 0.6 : 0.1 to 0.6 : 0.3.
+        let b = &results[0];
+        assert_close(&b.low, &[600.0 / 7.0, 100.0 / 7.0]);
+        assert_close(&b.high, &[200.0 / 3.0, 100.0 / 3.0]);
+        assert!((b.swing - (600.0 / 7.0 - 200.0 / 3.0)).abs() < 1e-9);
+        assert!((b.derivative[0] - (200.0 / 3.0 - 600.0 / 7.0) / 0.2).abs() < 1e-9);
+        // A's prior moves by a tenth of the total, from 0.8 to 1.2.
+        let prior = &results[1];
+        assert_close(&prior.low, &[48.0 / 0.68, 20.0 / 0.68]);
+        assert_close(&prior.high, &[72.0 / 0.92, 20.0 / 0.92]);
+    }
+
+    #[test]
+    fn likelihoods_stay_between_0_and_1() {
+        let mut data = example();
+        data.likelihoods[0] = vec![0.95, 0.05];
+        let results = sensitivities(&data, 0.1);
+        let a = results
+            .iter()
+            .find(|s| s.input == Input::Likelihood(0, 0))
+            .unwrap();
+        // 85% to 100%, so the step is 0.15 wide.
+        assert_close(&a.low, &[8500.0 / 90.0, 500.0 / 90.0]);
+        assert_close(&a.high, &[10000.0 / 105.0, 500.0 / 105.0]);
+        assert!((a.derivative[0] - (a.high[0] - a.low[0]) / 0.15).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,157 @@
+use serde::{Deserialize, Serialize};
+
+/// The unit evidence strength and log-odds are shown in. Everything is
+/// computed in decibels and converted for display.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum StrengthUnit {
+    #[default]
+    Decibels,
+    Bits,
+    Nats,
+    /// Plain odds ratios rather than their logarithm.
+    Ratio,
+}
+
+impl StrengthUnit {
+    pub const ALL: [StrengthUnit; 4] = [
+        StrengthUnit::Decibels,
+        StrengthUnit::Bits,
+        StrengthUnit::Nats,
+        StrengthUnit::Ratio,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            StrengthUnit::Decibels => "dB",
+            StrengthUnit::Bits => "bits",
+            StrengthUnit::Nats => "nats",
+            StrengthUnit::Ratio => "ratio",
+        }
+    }
+
+    /// The inverse of `name`, ignoring case.
+    pub fn from_name(name: &str) -> Option<StrengthUnit> {
+        StrengthUnit::ALL
+            .into_iter()
+            .find(|unit| unit.name().eq_ignore_ascii_case(name.trim()))
+    }
+
+    /// A value in decibels, converted to this unit.
+    pub fn from_db(&self, db: f64) -> f64 {
+        match self {
+            StrengthUnit::Decibels => db,
+            StrengthUnit::Bits => db / (10.0 * 2f64.log10()),
+            StrengthUnit::Nats => db * std::f64::consts::LN_10 / 10.0,
+            StrengthUnit::Ratio => 10f64.powf(db / 10.0),
+        }
+    }
+
+    /// A value in decibels as a number in this unit, without the unit: signed
+    /// for logarithmic units, e.g. `+3.0`, and plain for ratios, e.g. `2.00`.
+    pub fn format_value(&self, db: f64) -> String {
+        let value = self.from_db(db);
+        if value.is_nan() {
+            return "–".to_string();
+        }
+        if value == f64::INFINITY {
+            return if *self == StrengthUnit::Ratio {
+                "∞"
+            } else {
+                "+∞"
+            }
+            .to_string();
+        }
+        if value == f64::NEG_INFINITY {
+            return "−∞".to_string();
+        }
+        match self {
+            StrengthUnit::Decibels => format!("{:+.1}", value),
+            StrengthUnit::Bits | StrengthUnit::Nats => format!("{:+.2}", value),
+            StrengthUnit::Ratio if value >= 100.0 => format!("{:.0}", value),
+            StrengthUnit::Ratio if value >= 10.0 => format!("{:.1}", value),
+            StrengthUnit::Ratio => format!("{:.2}", value),
+        }
+    }
+
+    /// A value in decibels with this unit, e.g. `+3.0 dB` or `2.00×`.
+    pub fn format(&self, db: f64) -> String {
+        match self {
+            StrengthUnit::Ratio => format!("{}×", self.format_value(db)),
+            _ => format!("{} {}", self.format_value(db), self.name()),
+        }
+    }
+}
+
+/// The hypothesis with the single highest value, or `None` on a tie.
+pub fn strongest(values: &[f64]) -> Option<usize> {
+    let mut best: Option<usize> = None;
+    let mut best_value = f64::NEG_INFINITY;
+    let mut tied = false;
+    for (idx, value) in values.iter().enumerate() {
+        match value.partial_cmp(&best_value) {
+            Some(std::cmp::Ordering::Greater) => {
+                best = Some(idx);
+                best_value = *value;
+                tied = false;
+            }
+            Some(std::cmp::Ordering::Equal) => tied = true,
+            _ => {}
+        }
+    }
+    if tied {
+        None
+    } else {
+        best
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn converts_decibels_to_each_unit() {
+        // A factor of 10 is 10 dB, log2(10) bits and ln(10) nats.
+        assert_eq!(StrengthUnit::Decibels.from_db(10.0), 10.0);
+        assert!((StrengthUnit::Bits.from_db(10.0) - 10f64.log2()).abs() < 1e-12);
+        assert!((StrengthUnit::Nats.from_db(10.0) - 10f64.ln()).abs() < 1e-12);
+        assert!((StrengthUnit::Ratio.from_db(10.0) - 10.0).abs() < 1e-12);
+        assert!((StrengthUnit::Ratio.from_db(-10.0) - 0.1).abs() < 1e-12);
+    }
+
+    #[test]
+    fn formats_with_the_unit() {
+        // A factor of 2.
+        let db = 10.0 * 2f64.log10();
+        assert_eq!(StrengthUnit::Decibels.format(db), "+3.0 dB");
+        assert_eq!(StrengthUnit::Bits.format(db), "+1.00 bits");
+        assert_eq!(StrengthUnit::Nats.format(-db), "-0.69 nats");
+        assert_eq!(StrengthUnit::Ratio.format(db), "2.00×");
+        assert_eq!(StrengthUnit::Ratio.format(13.0), "20.0×");
+        assert_eq!(StrengthUnit::Ratio.format(30.0), "1000×");
+        assert_eq!(StrengthUnit::Bits.format(f64::INFINITY), "+∞ bits");
+        assert_eq!(StrengthUnit::Ratio.format(f64::INFINITY), "∞×");
+        assert_eq!(StrengthUnit::Decibels.format(f64::NEG_INFINITY), "−∞ dB");
+        assert_eq!(StrengthUnit::Decibels.format_value(f64::NAN), "–");
+    }
+
+    #[test]
+    fn names_round_trip() {
+        for unit in StrengthUnit::ALL {
+            assert_eq!(StrengthUnit::from_name(unit.name()), Some(unit));
+        }
+        assert_eq!(
+            StrengthUnit::from_name(" DB "),
+            Some(StrengthUnit::Decibels)
+        );
+        assert_eq!(StrengthUnit::from_name("hartleys"), None);
+    }
+
+    #[test]
+    fn strongest_needs_a_single_highest_value() {
+        assert_eq!(strongest(&[1.0, 3.0, 2.0]), Some(1));
+        assert_eq!(strongest(&[3.0, 3.0, 2.0]), None);
+        assert_eq!(strongest(&[f64::NAN, 1.0]), Some(1));
+        assert_eq!(strongest(&[]), None);
+    }
+}
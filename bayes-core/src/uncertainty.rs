@@ -0,0 +1,235 @@
+use crate::calc::{percentize, recalculate};
+use crate::decimal::{format_percent, parse_percent};
+use crate::model::BayesData;
+use crate::sampling::Rng;
+use crate::validate::check_spread;
+use serde::{Deserialize, Serialize};
+
+/// How uncertain a prior or likelihood is. Ranges are in the same units as the
+/// value they belong to; a Beta distribution is over a proportion, which for
+/// priors is taken as a share of the total prior odds.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub enum Spread {
+    Range(f64, f64),
+    Beta(f64, f64),
+}
+
+impl Spread {
+    pub fn sample(&self, rng: &mut Rng, beta_scale: f64) -> f64 {
+        match *self {
+            Spread::Range(low, high) => low + (high - low) * rng.uniform(),
+            Spread::Beta(a, b) => beta_scale * rng.beta(a, b),
+        }
+    }
+
+    /// Parses `[low, high]`, `low-high` or `Beta(a, b)`, where range bounds are
+    /// multiplied by `scale`: 0.01 for the percentages of likelihoods, which
+    /// can't go above 100%. Blank text means no spread.
+    pub fn parse(text: &str, scale: f64) -> Result<Option<Spread>, String> {
+        let text = text.trim();
+        if text.is_empty() {
+            return Ok(None);
+        }
+        let number = |s: &str| {
+            s.trim()
+                .trim_end_matches('%')
+                .trim()
+                .parse::<f64>()
+                .map_err(|_| format!("'{}' is not a number", s.trim()))
+        };
+        if text.to_lowercase().starts_with("beta(") && text.ends_with(')') {
+            let (a, b) = text[5..text.len() - 1]
+                .split_once(',')
+                .ok_or_else(|| "Beta needs two parameters, e.g. Beta(2, 3)".to_string())?;
+            let spread = Spread::Beta(number(a)?, number(b)?);
+            check_spread(&spread, scale == 0.01).map_err(|invalid| invalid.message)?;
+            return Ok(Some(spread));
+        }
+        let inner = text.trim_start_matches('[').trim_end_matches(']');
+        // A `-` between the bounds, rather than a sign or in an exponent like 1e-5.
+        let dash = inner
+            .char_indices()
+            .skip(1)
+            .find(|&(idx, c)| c == '-' && !inner[..idx].ends_with(['e', 'E']))
+            .map(|(idx, _)| (&inner[..idx], &inner[idx + 1..]));
+        let (low, high) = inner
+            .split_once(',')
+            .or(dash)
+            .ok_or_else(|| "Expected a range like [40%, 60%] or Beta(2, 3)".to_string())?;
+        // Percentages are read exactly, see `parse_percent`.
+        let bound = |s: &str| -> Result<f64, String> {
+            if scale == 0.01 {
+                parse_percent(s.trim().trim_end_matches('%'))
+                    .map_err(|_| format!("'{}' is not a number", s.trim()))
+            } else {
+                Ok(scale * number(s)?)
+            }
+        };
+        let spread = Spread::Range(bound(low)?, bound(high)?);
+        check_spread(&spread, scale == 0.01).map_err(|invalid| invalid.message)?;
+        Ok(Some(spread))
+    }
+
+    /// The inverse of `parse`; `percent` adds a % sign to range bounds.
+    pub fn format(&self, scale: f64, percent: bool) -> String {
+        let unit = if percent { "%" } else { "" };
+        match *self {
+            Spread::Range(low, high) if scale == 0.01 => format!(
+                "[{}{unit}, {}{unit}]",
+                format_percent(low),
+                format_percent(high)
+            ),
+            Spread::Range(low, high) => {
+                format!("[{}{unit}, {}{unit}]", low / scale, high / scale)
+            }
+            Spread::Beta(a, b) => format!("Beta({}, {})", a, b),
+        }
+    }
+}
+
+/// A credible interval for one hypothesis's posterior, in percent.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Interval {
+    pub low: f64,
+    pub median: f64,
+    pub high: f64,
+}
+
+fn quantile(sorted: &[f64], q: f64) -> f64 {
+    let pos = q * (sorted.len() - 1) as f64;
+    let (lower, upper) = (pos.floor() as usize, pos.ceil() as usize);
+    sorted[lower] + (sorted[upper] - sorted[lower]) * (pos - lower as f64)
+}
+
+/// Draws `samples` versions of the model from its spreads, runs each through
+/// `recalculate`, and summarises each hypothesis's posterior by its median and
+/// central `level` credible interval. `None` if nothing has a spread.
+pub fn posterior_intervals(data: &BayesData, samples: usize, level: f64) -> Option<Vec<Interval>> {
+    if !data.has_spread() || samples == 0 {
+        return None;
+    }
+    let mut rng = Rng::new(0x5eed);
+    let prior_total: f64 = data.prior_odds.iter().sum();
+    let likelihoods = data.effective_likelihoods();
+    let mut draws: Vec<Vec<f64>> = vec![Vec::with_capacity(samples); data.hypotheses.len()];
+
+    for _ in 0..samples {
+        let prior = (0..data.hypotheses.len())
+            .map(|hyp_idx| match data.prior_spread(hyp_idx) {
+                Some(spread) => spread.sample(&mut rng, prior_total),
+                None => data.prior_odds[hyp_idx],
+            })
+            .collect();
+        let sampled = likelihoods
+            .iter()
+            .enumerate()
+            .map(|(ev_idx, row)| {
+                if !data.counts(ev_idx) {
+                    return row.clone();
+                }
+                row.iter()
+                    .enumerate()
+                    .map(
+                        |(hyp_idx, likelihood)| match data.likelihood_spread(ev_idx, hyp_idx) {
+                            Some(spread) => spread.sample(&mut rng, 1.0).clamp(0.0, 1.0),
+                            None => *likelihood,
+                        },
+                    )
+                    .collect()
+            })
+            .collect();
+        let posterior = percentize(recalculate(prior, sampled));
+        for (hyp_idx, p) in posterior.into_iter().enumerate() {
+            draws[hyp_idx].push(p);
+        }
+    }
+
+    let tail = (1.0 - level) / 2.0;
+    Some(
+        draws
+            .into_iter()
+            .map(|mut hyp_draws| {
+                hyp_draws.sort_by(f64::total_cmp);
+                Interval {
+                    low: quantile(&hyp_draws, tail),
+                    median: quantile(&hyp_draws, 0.5),
+                    high: quantile(&hyp_draws, 1.0 - tail),
+                }
+            })
+            .collect(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_ranges_and_beta() {
+        let range = |low, high| Ok(Some(Spread::Range(low, high)));
+        assert_eq!(Spread::parse("[40%, 60%]", 0.01), range(0.4, 0.6));
+        assert_eq!(Spread::parse("40-60", 0.01), range(0.4, 0.6));
+        assert_eq!(Spread::parse("2, 3.5", 1.0), range(2.0, 3.5));
+        // A - in an exponent isn't the one between the bounds.
+        assert_eq!(Spread::parse("1e-5-2E-5", 1.0), range(1e-5, 2e-5));
+        assert_eq!(Spread::parse("[1e-3%-2e-3%]", 0.01), range(1e-5, 2e-5));
+        assert_eq!(
+            Spread::parse("beta(2, 3)", 0.01),
+            Ok(Some(Spread::Beta(2.0, 3.0)))
+        );
+        assert_eq!(Spread::parse("  ", 0.01), Ok(None));
+    }
+
+    #[test]
+    fn rejects_bad_spreads() {
+        for (text, scale) in [
+            ("[60%, 40%]", 0.01),
+            ("[-1, 2]", 1.0),
+            ("Beta(0, 1)", 0.01),
+            ("Beta(2, -3)", 1.0),
+            ("Beta(2)", 1.0),
+            ("[half, 60%]", 0.01),
+            ("50%", 0.01),
+        ] {
+            assert!(Spread::parse(text, scale).is_err(), "{}", text);
+        }
+        // Priors are relative weights, but likelihoods are probabilities.
+        assert_eq!(
+            Spread::parse("[50%, 120%]", 0.01),
+            Err("A likelihood's range can't go above 100%".to_string())
+        );
+        assert!(Spread::parse("[50, 120]", 1.0).is_ok());
+    }
+
+    #[test]
+    fn format_round_trips() {
+        for (spread, scale) in [
+            (Spread::Range(0.07, 1.0 / 3.0), 0.01),
+            (Spread::Range(1e-5, 2e-5), 1.0),
+            (Spread::Beta(0.5, 12.0), 0.01),
+        ] {
+            for percent in [true, false] {
+                let text = spread.format(scale, percent);
+                assert_eq!(Spread::parse(&text, scale), Ok(Some(spread)), "{}", text);
+            }
+        }
+    }
+
+    #[test]
+    fn intervals_contain_their_median() {
+        let mut data = BayesData {
+            hypotheses: vec!["A".to_string(), "B".to_string()],
+            prior_odds: vec![1.0, 1.0],
+            evidence: vec!["E".to_string()],
+            likelihoods: vec![vec![0.8, 0.2]],
+            ..BayesData::default()
+        };
+        assert_eq!(posterior_intervals(&data, 100, 0.9), None);
+        data.set_likelihood_spread(0, 0, Some(Spread::Range(0.6, 1.0)));
+        let intervals = posterior_intervals(&data, 1000, 0.9).unwrap();
+        // A's posterior is 0.6 / 0.8 to 1.0 / 1.2 of the way, 75% to 83.3%.
+        let a = intervals[0];
+        assert!(75.0 <= a.low && a.low <= a.median && a.median <= a.high && a.high <= 83.4);
+        assert!((a.median + intervals[1].median - 100.0).abs() < 1e-9);
+    }
+}
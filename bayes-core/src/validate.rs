@@ -0,0 +1,515 @@
+//! Checks on imported analyses, shared by the file formats so a value that's
+//! wrong in one is wrong in all of them, for the same reason.
+
+use crate::decimal::parse_percent;
+use crate::diagnostic::{Diagnostic, Located};
+use crate::distribution::Distribution;
+use crate::evidence::EvidenceKind;
+use crate::model::BayesData;
+use crate::uncertainty::Spread;
+use std::str::FromStr;
+
+/// How far, in percentage points, a posterior in a file may be from the
+/// recalculated one before importing warns. Enough for a posterior written by
+/// hand to one decimal place.
+pub const POSTERIOR_TOLERANCE: f64 = 0.1;
+
+/// How far a hypothesis's outcome likelihoods may add up to from 1, as a
+/// probability: a tenth of a percentage point, e.g. for three outcomes
+/// written as 33.3%.
+pub(crate) const OUTCOME_TOTAL_TOLERANCE: f64 = 0.001;
+
+/// What's wrong with a value and how to fix it, for the caller to place.
+pub(crate) struct Invalid {
+    pub message: String,
+    pub fix: String,
+}
+
+impl Invalid {
+    fn new(message: impl Into<String>, fix: impl Into<String>) -> Invalid {
+        Invalid {
+            message: message.into(),
+            fix: fix.into(),
+        }
+    }
+}
+
+pub(crate) fn check_prior(prior: f64) -> Result<(), Invalid> {
+    if prior.is_finite() && prior >= 0.0 {
+        Ok(())
+    } else {
+        Err(Invalid::new(
+            "A prior must be 0 or more",
+            "Use 0 or a positive number; priors are relative weights",
+        ))
+    }
+}
+
+/// A likelihood, as a probability rather than a percentage.
+pub(crate) fn check_likelihood(p: f64) -> Result<(), Invalid> {
+    if (0.0..=1.0).contains(&p) {
+        Ok(())
+    } else {
+        Err(Invalid::new(
+            "A likelihood must be between 0% and 100%",
+            "Use a percentage from 0% to 100%",
+        ))
+    }
+}
+
+/// Checks that a measurement's distribution has a finite location and a
+/// positive scale, or for `Uniform`, a low end below the high one.
+pub(crate) fn check_distribution(dist: &Distribution) -> Result<(), Invalid> {
+    let fix = match *dist {
+        Distribution::Normal { mean, sd } if !(mean.is_finite() && sd > 0.0) => {
+            "Use an sd above 0, e.g. Normal(0, 1)"
+        }
+        Distribution::LogNormal { mu, sigma } if !(mu.is_finite() && sigma > 0.0) => {
+            "Use a σ above 0, e.g. LogNormal(0, 1)"
+        }
+        Distribution::Exponential { rate } if !(rate > 0.0 && rate.is_finite()) => {
+            "Use a rate above 0, e.g. Exponential(1)"
+        }
+        Distribution::Uniform { low, high } if !(low.is_finite() && high > low) => {
+            "Write the lower end first, e.g. Uniform(0, 1)"
+        }
+        _ => return Ok(()),
+    };
+    Err(Invalid::new(
+        format!("Invalid parameters for {}", dist),
+        fix,
+    ))
+}
+
+/// Checks the spread of a prior or, if `likelihood`, of a likelihood, whose
+/// range is of probabilities and so can't go above 1.
+pub(crate) fn check_spread(spread: &Spread, likelihood: bool) -> Result<(), Invalid> {
+    match *spread {
+        Spread::Beta(a, b) if !(a.is_finite() && b.is_finite() && a > 0.0 && b > 0.0) => {
+            Err(Invalid::new(
+                "Beta parameters must be positive",
+                "Use two numbers above 0, e.g. Beta(2, 3)",
+            ))
+        }
+        Spread::Range(low, high)
+            if !(low.is_finite() && high.is_finite()) || low < 0.0 || high < low =>
+        {
+            Err(Invalid::new(
+                "A range needs 0 ≤ low ≤ high",
+                "Write the lower end first, e.g. [40%, 60%]",
+            ))
+        }
+        Spread::Range(_, high) if likelihood && high > 1.0 => Err(Invalid::new(
+            "A likelihood's range can't go above 100%",
+            "Keep both ends of the range between 0% and 100%",
+        )),
+        _ => Ok(()),
+    }
+}
+
+/// Checks one hypothesis's row of a categorical table, whose outcomes
+/// between them must be certain.
+pub(crate) fn check_outcome_total(row: &[f64]) -> Result<(), Invalid> {
+    let total: f64 = row.iter().sum();
+    if (total - 1.0).abs() <= OUTCOME_TOTAL_TOLERANCE {
+        Ok(())
+    } else {
+        Err(Invalid::new(
+            format!(
+                "These outcomes' likelihoods add up to {:.1}%, not 100%",
+                100.0 * total
+            ),
+            "Make the likelihoods of the outcomes under each hypothesis add up to 100%",
+        ))
+    }
+}
+
+pub(crate) fn check_utility(utility: f64) -> Result<(), Invalid> {
+    if utility.is_finite() {
+        Ok(())
+    } else {
+        Err(Invalid::new(
+            "A utility must be a finite number",
+            "Write the utility as a number, e.g. 10",
+        ))
+    }
+}
+
+pub(crate) fn parse_number(at: Located<'_>, example: &str) -> Result<f64, Diagnostic> {
+    f64::from_str(at.text).map_err(|_| {
+        at.error(
+            "This isn't a number",
+            format!("Write a number here, e.g. '{}'", example),
+        )
+    })
+}
+
+/// A likelihood written as a percentage like `30%`, where the `%` is
+/// optional, which must be between 0% and 100%.
+pub(crate) fn parse_likelihood(at: Located<'_>) -> Result<f64, Diagnostic> {
+    let p = parse_percent(at.text.trim_end_matches('%')).map_err(|_| {
+        at.error(
+            "This isn't a percentage",
+            "Write the likelihood as a percentage, e.g. '30%'",
+        )
+    })?;
+    check_likelihood(p).map_err(|invalid| at.invalid(invalid))?;
+    Ok(p)
+}
+
+/// Checks a posterior given in a file, in percent, against the recalculated
+/// one. A mismatch means the file was edited by hand or is stale.
+pub(crate) fn check_posterior(stated: f64, recalculated: f64) -> Result<(), Invalid> {
+    if (stated - recalculated).abs() <= POSTERIOR_TOLERANCE {
+        Ok(())
+    } else {
+        Err(Invalid::new(
+            format!(
+                "The file gives {}% here, but the prior and evidence give {:.2}%",
+                stated, recalculated
+            ),
+            "The file may have been edited or saved by an older version; \
+             the recalculated posterior is used, and exporting again updates the file",
+        ))
+    }
+}
+
+/// Checks that `name` isn't among the hypotheses before it.
+pub(crate) fn check_new_hypothesis(earlier: &[&str], name: &str) -> Result<(), Invalid> {
+    if earlier.contains(&name) {
+        Err(Invalid::new(
+            "This hypothesis is already listed",
+            "Give each hypothesis a different name",
+        ))
+    } else {
+        Ok(())
+    }
+}
+
+/// Everything wrong with `data` as a whole, for formats that hold it as
+/// arrays rather than lines; each problem is placed by its field, e.g.
+/// `likelihoods[1][0]`.
+pub fn check_data(data: &BayesData) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+    let mut field = |path: String, invalid: Invalid| {
+        diagnostics.push(Diagnostic::in_field(path, invalid.message, invalid.fix));
+    };
+    let count = data.hypotheses.len();
+    let mismatch = |what: &str, per: &str, expected: usize, found: usize| {
+        Invalid::new(
+            format!(
+                "Expected {} {}, one per {}, but found {}",
+                expected, what, per, found
+            ),
+            format!("Add or remove entries so there's one per {}", per),
+        )
+    };
+
+    if count == 0 {
+        field(
+            "hypotheses".to_string(),
+            Invalid::new(
+                "There are no hypotheses",
+                "Add at least one hypothesis and its prior",
+            ),
+        );
+    }
+    let names: Vec<&str> = data.hypotheses.iter().map(String::as_str).collect();
+    for (hyp_idx, name) in names.iter().enumerate() {
+        if let Err(invalid) = check_new_hypothesis(&names[..hyp_idx], name) {
+            field(format!("hypotheses[{}]", hyp_idx), invalid);
+        }
+    }
+    if data.prior_odds.len() != count {
+        field(
+            "prior_odds".to_string(),
+            mismatch("priors", "hypothesis", count, data.prior_odds.len()),
+        );
+    }
+    for (hyp_idx, prior) in data.prior_odds.iter().enumerate() {
+        if let Err(invalid) = check_prior(*prior) {
+            field(format!("prior_odds[{}]", hyp_idx), invalid);
+        }
+    }
+
+    let rows = data.evidence.len();
+    if data.likelihoods.len() != rows {
+        field(
+            "likelihoods".to_string(),
+            mismatch(
+                "likelihood rows",
+                "evidence row",
+                rows,
+                data.likelihoods.len(),
+            ),
+        );
+    }
+    for (ev_idx, row) in data.likelihoods.iter().enumerate() {
+        if row.len() != count {
+            field(
+                format!("likelihoods[{}]", ev_idx),
+                mismatch("likelihoods", "hypothesis", count, row.len()),
+            );
+        }
+        for (hyp_idx, p) in row.iter().enumerate() {
+            if let Err(invalid) = check_likelihood(*p) {
+                field(format!("likelihoods[{}][{}]", ev_idx, hyp_idx), invalid);
+            }
+        }
+    }
+    // These may be shorter than the evidence, as missing entries have
+    // defaults, but not longer.
+    for (name, len) in [
+        ("active", data.active.len()),
+        ("planned", data.planned.len()),
+        ("kinds", data.kinds.len()),
+        ("entry_modes", data.entry_modes.len()),
+        ("likelihood_spread", data.likelihood_spread.len()),
+    ] {
+        if len > rows {
+            field(
+                name.to_string(),
+                Invalid::new(
+                    format!("Has {} entries for {} evidence rows", len, rows),
+                    "Remove the extra entries, or add the evidence they belong to",
+                ),
+            );
+        }
+    }
+    // The posterior is optional, as it's recalculated, but if it's given it's
+    // checked against each hypothesis.
+    if !data.posterior_odds.is_empty() && data.posterior_odds.len() != count {
+        field(
+            "posterior_odds".to_string(),
+            mismatch("posteriors", "hypothesis", count, data.posterior_odds.len()),
+        );
+    }
+    if data.prior_spread.len() > count {
+        field(
+            "prior_spread".to_string(),
+            mismatch(
+                "prior spreads",
+                "hypothesis",
+                count,
+                data.prior_spread.len(),
+            ),
+        );
+    }
+    for (hyp_idx, spread) in data.prior_spread.iter().enumerate() {
+        if let Some(Err(invalid)) = spread.map(|spread| check_spread(&spread, false)) {
+            field(format!("prior_spread[{}]", hyp_idx), invalid);
+        }
+    }
+    for (ev_idx, row) in data.likelihood_spread.iter().enumerate() {
+        if row.len() > count {
+            field(
+                format!("likelihood_spread[{}]", ev_idx),
+                mismatch("likelihood spreads", "hypothesis", count, row.len()),
+            );
+        }
+        for (hyp_idx, spread) in row.iter().enumerate() {
+            if let Some(Err(invalid)) = spread.map(|spread| check_spread(&spread, true)) {
+                field(
+                    format!("likelihood_spread[{}][{}]", ev_idx, hyp_idx),
+                    invalid,
+                );
+            }
+        }
+    }
+    for (ev_idx, kind) in data.kinds.iter().enumerate() {
+        match kind {
+            EvidenceKind::Binary => {}
+            EvidenceKind::Categorical(categorical) => {
+                let path = format!("kinds[{}].Categorical", ev_idx);
+                if categorical.table.len() != count {
+                    field(
+                        format!("{}.table", path),
+                        mismatch("table rows", "hypothesis", count, categorical.table.len()),
+                    );
+                }
+                for (hyp_idx, row) in categorical.table.iter().enumerate() {
+                    if row.len() != categorical.outcomes.len() {
+                        field(
+                            format!("{}.table[{}]", path, hyp_idx),
+                            mismatch(
+                                "likelihoods",
+                                "outcome",
+                                categorical.outcomes.len(),
+                                row.len(),
+                            ),
+                        );
+                    }
+                    for (outcome_idx, p) in row.iter().enumerate() {
+                        if let Err(invalid) = check_likelihood(*p) {
+                            field(
+                                format!("{}.table[{}][{}]", path, hyp_idx, outcome_idx),
+                                invalid,
+                            );
+                        }
+                    }
+                    if let Err(invalid) = check_outcome_total(row) {
+                        field(format!("{}.table[{}]", path, hyp_idx), invalid);
+                    }
+                }
+                if categorical.observed >= categorical.outcomes.len() {
+                    field(
+                        format!("{}.observed", path),
+                        Invalid::new(
+                            "The observed outcome isn't one of the outcomes",
+                            "Use the index of an outcome, counting from 0",
+                        ),
+                    );
+                }
+            }
+            EvidenceKind::Continuous(continuous) => {
+                let path = format!("kinds[{}].Continuous.distributions", ev_idx);
+                if continuous.distributions.len() != count {
+                    field(
+                        path.clone(),
+                        mismatch(
+                            "distributions",
+                            "hypothesis",
+                            count,
+                            continuous.distributions.len(),
+                        ),
+                    );
+                }
+                for (hyp_idx, dist) in continuous.distributions.iter().enumerate() {
+                    if let Err(invalid) = check_distribution(dist) {
+                        field(format!("{}[{}]", path, hyp_idx), invalid);
+                    }
+                }
+            }
+        }
+    }
+    for (ev_idx, mode) in data.entry_modes.iter().enumerate() {
+        if mode.reference().is_some_and(|reference| reference >= count) {
+            field(
+                format!("entry_modes[{}]", ev_idx),
+                Invalid::new(
+                    "The reference hypothesis doesn't exist",
+                    "Use the index of a hypothesis, counting from 0",
+                ),
+            );
+        }
+    }
+
+    if data.utilities.len() != data.actions.len() {
+        field(
+            "utilities".to_string(),
+            mismatch(
+                "utility rows",
+                "action",
+                data.actions.len(),
+                data.utilities.len(),
+            ),
+        );
+    }
+    for (action_idx, row) in data.utilities.iter().enumerate() {
+        if row.len() != count {
+            field(
+                format!("utilities[{}]", action_idx),
+                mismatch("utilities", "hypothesis", count, row.len()),
+            );
+        }
+        for (hyp_idx, utility) in row.iter().enumerate() {
+            if let Err(invalid) = check_utility(*utility) {
+                field(format!("utilities[{}][{}]", action_idx, hyp_idx), invalid);
+            }
+        }
+    }
+    diagnostics
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::evidence::{Categorical, Continuous};
+
+    #[test]
+    fn outcome_totals_allow_rounding() {
+        assert!(check_outcome_total(&[0.333, 0.333, 0.334]).is_ok());
+        assert!(check_outcome_total(&[0.333, 0.333, 0.333]).is_ok());
+        assert!(check_outcome_total(&[0.5, 0.3]).is_err());
+        assert!(check_outcome_total(&[0.6, 0.6]).is_err());
+    }
+
+    #[test]
+    fn posteriors_are_checked_to_a_tenth_of_a_point() {
+        assert!(check_posterior(25.09, 25.0).is_ok());
+        assert!(check_posterior(24.91, 25.0).is_ok());
+        assert!(check_posterior(25.11, 25.0).is_err());
+        assert!(check_posterior(24.89, 25.0).is_err());
+    }
+
+    #[test]
+    fn check_data_rejects_tables_that_do_not_sum_to_1() {
+        let mut data = BayesData::default();
+        data.set_kind(
+            0,
+            EvidenceKind::Categorical(Categorical {
+                outcomes: vec!["Low".to_string(), "High".to_string()],
+                table: vec![vec![0.2, 0.8], vec![0.5, 0.3]],
+                observed: 1,
+            }),
+        );
+        let diagnostics = check_data(&data);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].text, "kinds[0].Categorical.table[1]");
+        assert!(diagnostics[0].message.contains("80.0%"));
+    }
+
+    #[test]
+    fn check_data_rejects_bad_spreads_and_distributions() {
+        let mut data = BayesData {
+            prior_spread: vec![Some(Spread::Beta(0.0, 2.0)), None],
+            likelihood_spread: vec![vec![Some(Spread::Range(0.6, 0.4)), None, None]],
+            ..BayesData::default()
+        };
+        data.set_kind(
+            0,
+            EvidenceKind::Continuous(Continuous {
+                distributions: vec![
+                    Distribution::Normal { mean: 0.0, sd: 1.0 },
+                    Distribution::Exponential { rate: -1.0 },
+                ],
+                observed: 0.5,
+            }),
+        );
+        let texts: Vec<String> = check_data(&data)
+            .into_iter()
+            .map(|diagnostic| diagnostic.text)
+            .collect();
+        assert_eq!(
+            texts,
+            vec![
+                "prior_spread[0]",
+                "likelihood_spread[0]",
+                "likelihood_spread[0][0]",
+                "kinds[0].Continuous.distributions[1]",
+            ]
+        );
+    }
+
+    #[test]
+    fn spreads_and_distributions_are_checked_the_same_everywhere() {
+        assert!(check_spread(&Spread::Beta(2.0, 3.0), true).is_ok());
+        assert!(check_spread(&Spread::Beta(2.0, -3.0), true).is_err());
+        assert!(check_spread(&Spread::Range(2.0, 3.0), false).is_ok());
+        assert!(check_spread(&Spread::Range(0.2, 1.5), true).is_err());
+        assert!(check_distribution(&Distribution::LogNormal {
+            mu: 0.0,
+            sigma: 0.0
+        })
+        .is_err());
+        assert!(check_distribution(&Distribution::Uniform {
+            low: 1.0,
+            high: 1.0
+        })
+        .is_err());
+        assert!(check_distribution(&Distribution::Normal { mean: 3.0, sd: 0.5 }).is_ok());
+        // The markdown reader uses the same checks through the parsers.
+        assert!(Spread::parse("Beta(0, 1)", 0.01).is_err());
+        assert!(Distribution::parse("Normal(0, -1)").is_err());
+    }
+}
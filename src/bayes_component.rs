@@ -1,13 +1,20 @@
 // bayes_component.rs
-use crate::chance_component::percentize;
 use crate::chance_component::ChanceCallback;
 use crate::chance_component::Kind;
-use crate::evidence_component::EvidenceCallback;
+use crate::evidence_component::{EvidenceCallback, KindChoice};
+use crate::layout::{column_width, palette_css};
+use crate::library_component::{LibraryCallback, LibraryComponent};
 use crate::storage::decode_bayes_data;
 use crate::storage::encode_bayes_data;
-use crate::storage::export_to_markdown;
-use crate::storage::parse_markdown;
-use crate::storage::BayesData;
+use crate::storage::export_file;
+use crate::storage::export_predictions_csv;
+use bayes_core::{
+    evidence_influence, hypothesis_letters, log_odds_in_db, log_recalculate_to,
+    natural_frequencies, percentize, posterior_intervals, recalculate, recalculate_to, ruled_out,
+    sensitivities, value_of_information, BayesData, Categorical, Continuous, Diagnostic,
+    Distribution, EntryMode, EvidenceKind, FileFormat, History, Interval, Library, Prediction,
+    PredictionLog, Spread, StrengthUnit,
+};
 use gloo::utils::document;
 use js_sys::Array;
 use serde::{Deserialize, Serialize};
@@ -17,45 +24,69 @@ use web_sys::FileReader;
 use web_sys::HtmlElement;
 use web_sys::Url;
 
+use crate::calibration_component::{CalibrationCallback, CalibrationComponent};
+use crate::decision_component::{DecisionCallback, DecisionComponent};
+use crate::diagnostics_component::DiagnosticsComponent;
+use crate::frequency_component::{FrequencyComponent, FrequencyView, POPULATION};
+use crate::sensitivity_component::SensitivityComponent;
 use crate::share_component::ShareComponent;
+use crate::trajectory_component::TrajectoryComponent;
 use crate::ChanceComponent;
 use crate::EvidenceComponent;
 use crate::ModalComponent;
 
-use gloo_storage::{SessionStorage, Storage};
+use gloo_storage::{LocalStorage, SessionStorage, Storage};
 use wasm_bindgen::JsCast;
 use yew::virtual_dom::AttrValue;
 
 use yew::prelude::*;
 
-pub fn recalculate(prior: Vec<f64>, likelihoods: Vec<Vec<f64>>) -> Vec<f64> {
-    prior
-        .into_iter()
-        .enumerate()
-        .map(|(hyp_idx, prior_val)| {
-            likelihoods
-                .iter()
-                .fold(prior_val, |acc, ev| acc * ev[hyp_idx])
-        })
-        .collect()
+fn save_data(data: &BayesData) {
+    let serialized = serde_json::to_string(&data).unwrap();
+    SessionStorage::set("bayes_component", serialized).unwrap();
+}
+
+fn save_library(library: &Library) {
+    let serialized = serde_json::to_string(&library).unwrap();
+    LocalStorage::set("bayes_library", serialized).unwrap();
+}
+
+fn save_predictions(predictions: &PredictionLog) {
+    let serialized = serde_json::to_string(&predictions).unwrap();
+    LocalStorage::set("bayes_predictions", serialized).unwrap();
+}
+
+fn save_document(document: Option<u64>) {
+    let serialized = serde_json::to_string(&document).unwrap();
+    SessionStorage::set("bayes_document", serialized).unwrap();
+}
+
+fn load_scratch() -> BayesData {
+    SessionStorage::get::<String>("bayes_component")
+        .ok()
+        .and_then(|serialized| serde_json::from_str::<BayesData>(&serialized).ok())
+        .unwrap_or_default()
 }
 
-pub fn recalculate_to(prior: Vec<f64>, likelihoods: Vec<Vec<f64>>, to: usize) -> Vec<f64> {
-    prior
-        .into_iter()
-        .enumerate()
-        .map(|(hyp_idx, prior_val)| {
-            likelihoods
-                .iter()
-                .take(to)
-                .fold(prior_val, |acc, ev| acc * ev[hyp_idx])
+/// Whether typing in `element` edits its text: text-like inputs, including
+/// numbers, text areas and anything contenteditable.
+fn is_editable(element: &HtmlElement) -> bool {
+    if element.is_content_editable() || element.tag_name() == "TEXTAREA" {
+        return true;
+    }
+    element
+        .dyn_ref::<web_sys::HtmlInputElement>()
+        .is_some_and(|input| {
+            !matches!(
+                input.type_().as_str(),
+                "range" | "checkbox" | "radio" | "button" | "submit" | "reset" | "file" | "color"
+            )
         })
-        .collect()
 }
 
-fn save_data(data: &BayesData) {
-    let serialized = serde_json::to_string(&data).unwrap();
-    SessionStorage::set("bayes_component", serialized).unwrap();
+fn save_history(history: &BayesHistory) {
+    let serialized = serde_json::to_string(&history).unwrap();
+    SessionStorage::set("bayes_history", serialized).unwrap();
 }
 
 fn save_prefs(prefs: &BayesPrefs) {
@@ -129,14 +160,115 @@ pub enum Msg {
     Clear,
     Export,
     FileSelected(Option<web_sys::File>),
-    FileContent(String),
+    /// A loaded file's name and content.
+    FileContent(String, String),
     ToggleModal,
     HideShare,
+    DismissDiagnostics,
     GenerateLink,
-    UpdateData(BayesData),
+    UpdateData(Box<BayesData>),
     ClearUrl,
     DeleteHypothesis(usize),
     DeleteEvidence(usize),
+    ToggleEvidence(usize),
+    TogglePlanned(usize),
+    Undo,
+    Redo,
+    EndEdit,
+    SwitchDocument(Option<u64>),
+    NewDocument,
+    RenameDocument(String),
+    DuplicateDocument,
+    DeleteDocument,
+    Resolve(Option<usize>),
+    RemovePrediction(usize),
+    ExportPredictions,
+    ToggleCalibration,
+    SetStrengthUnit(StrengthUnit),
+    SetFileFormat(FileFormat),
+    SetFrequencyView(FrequencyView),
+    ToggleTrajectory,
+    PriorSpread(usize, Option<Spread>),
+    LikelihoodSpread(usize, usize, Option<Spread>),
+    ToggleUncertainty,
+    ToggleSensitivity,
+    ToggleSortByInfluence,
+    ToggleDecision,
+    AddAction,
+    RenameAction(usize, String),
+    DeleteAction(usize),
+    Utility(usize, usize, f64),
+    SetKind(usize, KindChoice),
+    ObserveOutcome(usize, usize),
+    OutcomeLikelihood(usize, usize, usize, f64),
+    AddOutcome(usize),
+    RenameOutcome(usize, usize, String),
+    DeleteOutcome(usize, usize),
+    Observation(usize, f64),
+    Distribution(usize, usize, Distribution),
+    EntryMode(usize, EntryMode),
+    LikelihoodRatio(usize, usize, f64),
+}
+
+/// Monte Carlo draws behind the posterior credible intervals.
+const INTERVAL_SAMPLES: usize = 2000;
+const INTERVAL_LEVEL: f64 = 0.9;
+/// How far the sensitivity panel nudges each input.
+const SENSITIVITY_DELTA: f64 = 0.05;
+
+/// Edits that share a key are coalesced into one undo step while they happen
+/// back to back, e.g. every tick of a slider drag.
+#[derive(Debug, PartialEq, Eq)]
+enum EditKey {
+    Prior(usize),
+    Likelihood(usize, usize),
+    EvidenceLabel(usize),
+    OutcomeLikelihood(usize, usize, usize),
+    Observation(usize),
+    Distribution(usize, usize),
+    Utility(usize, usize),
+}
+
+type BayesHistory = History<BayesData, EditKey>;
+
+/// `None` if the message doesn't edit the model, otherwise its coalescing key.
+fn edit_key(msg: &Msg) -> Option<Option<EditKey>> {
+    match msg {
+        Msg::Prior(hyp_idx, ..) => Some(Some(EditKey::Prior(*hyp_idx))),
+        Msg::Evidence(ev_idx, hyp_idx, _) | Msg::LikelihoodRatio(ev_idx, hyp_idx, _) => {
+            Some(Some(EditKey::Likelihood(*ev_idx, *hyp_idx)))
+        }
+        Msg::EditEvidence(ev_idx, _) => Some(Some(EditKey::EvidenceLabel(*ev_idx))),
+        Msg::OutcomeLikelihood(ev_idx, hyp_idx, outcome_idx, _) => Some(Some(
+            EditKey::OutcomeLikelihood(*ev_idx, *hyp_idx, *outcome_idx),
+        )),
+        Msg::Utility(action_idx, hyp_idx, _) => Some(Some(EditKey::Utility(*action_idx, *hyp_idx))),
+        Msg::Observation(ev_idx, _) => Some(Some(EditKey::Observation(*ev_idx))),
+        Msg::Distribution(ev_idx, hyp_idx, _) => {
+            Some(Some(EditKey::Distribution(*ev_idx, *hyp_idx)))
+        }
+        Msg::AddHypothesis
+        | Msg::DeleteHypothesis(_)
+        | Msg::AddEvidence
+        | Msg::DeleteEvidence(_)
+        | Msg::ToggleEvidence(_)
+        | Msg::TogglePlanned(_)
+        | Msg::AddAction
+        | Msg::RenameAction(..)
+        | Msg::DeleteAction(_)
+        | Msg::PriorSpread(..)
+        | Msg::LikelihoodSpread(..)
+        | Msg::SetKind(..)
+        | Msg::EntryMode(..)
+        | Msg::ObserveOutcome(..)
+        | Msg::AddOutcome(_)
+        | Msg::RenameOutcome(..)
+        | Msg::DeleteOutcome(..)
+        | Msg::Clear
+        | Msg::FileContent(..)
+        | Msg::UpdateData(_) => Some(None),
+        _ => None,
+    }
 }
 
 #[derive(Properties, PartialEq, Eq)]
@@ -145,17 +277,56 @@ pub struct BayesProps {}
 pub struct BayesComponent {
     pub data: BayesData,
     onload: Option<Closure<dyn FnMut(Event)>>,
-    error_message: Option<String>,
+    /// Why the last file couldn't be loaded, or warnings about it, until dismissed.
+    diagnostics: Vec<Diagnostic>,
     prefs: BayesPrefs,
     show_link: bool,
     link: Option<String>,
+    history: BayesHistory,
+    library: Library,
+    /// The library document being edited, or `None` for the scratch document,
+    /// which only lives in this tab's session storage.
+    document: Option<u64>,
+    /// Resolved analyses, kept across sessions.
+    predictions: PredictionLog,
+    /// The posterior credible intervals and the data they were drawn from,
+    /// while uncertainty is shown, as drawing them is slow.
+    intervals: Option<(BayesData, Option<Vec<Interval>>)>,
     _hashchange_listener: Option<Closure<dyn FnMut(web_sys::Event)>>,
+    _keydown_listener: Option<Closure<dyn FnMut(KeyboardEvent)>>,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
 pub struct BayesPrefs {
     pub is_modal_open: bool,
     pub color: Vec<usize>,
+    #[serde(default)]
+    pub show_uncertainty: bool,
+    #[serde(default)]
+    pub show_sensitivity: bool,
+    #[serde(default)]
+    pub sort_by_influence: bool,
+    #[serde(default)]
+    pub show_decision: bool,
+    #[serde(default)]
+    pub show_calibration: bool,
+    #[serde(default)]
+    pub strength_unit: StrengthUnit,
+    #[serde(default)]
+    pub frequency_view: FrequencyView,
+    /// The format Export writes, and Load reads when the extension doesn't say.
+    #[serde(default)]
+    pub file_format: FileFormat,
+    #[serde(default)]
+    pub show_trajectory: bool,
+}
+
+impl BayesPrefs {
+    fn fit_colors(&mut self, num_hypotheses: usize) {
+        while self.color.len() < num_hypotheses {
+            self.color.push(self.color.len());
+        }
+    }
 }
 
 impl Component for BayesComponent {
@@ -168,22 +339,52 @@ impl Component for BayesComponent {
         let body = document.body().unwrap();
         let width = body.client_width();
 
-        let mut data = BayesData {
-            hypotheses: vec!["Hypothesis A".to_string(), "Hypothesis B".to_string()],
-            prior_odds: vec![50.0, 50.0],
-            posterior_odds: vec![50.0, 50.0],
-            evidence: vec!["Evidence 1".to_string()],
-            likelihoods: vec![vec![0.5, 0.5]],
-        };
+        let mut library = Library::default();
+        if let Ok(serialized) = LocalStorage::get::<String>("bayes_library") {
+            if let Ok(loaded_library) = serde_json::from_str::<Library>(&serialized) {
+                library = loaded_library;
+            }
+        }
+
+        let mut predictions = PredictionLog::default();
+        if let Ok(serialized) = LocalStorage::get::<String>("bayes_predictions") {
+            if let Ok(loaded_predictions) = serde_json::from_str::<PredictionLog>(&serialized) {
+                predictions = loaded_predictions;
+            }
+        }
+
+        let mut document = None;
+        if let Ok(serialized) = SessionStorage::get::<String>("bayes_document") {
+            if let Ok(Some(id)) = serde_json::from_str::<Option<u64>>(&serialized) {
+                if library.get(id).is_some() {
+                    document = Some(id);
+                }
+            }
+        }
 
         let mut prefs = BayesPrefs {
             is_modal_open: width > 1000,
             color: vec![0, 1, 2, 3, 4],
+            show_uncertainty: false,
+            show_sensitivity: false,
+            sort_by_influence: false,
+            show_decision: false,
+            show_calibration: false,
+            strength_unit: StrengthUnit::Decibels,
+            frequency_view: FrequencyView::Hidden,
+            file_format: FileFormat::Markdown,
+            show_trajectory: false,
+        };
+
+        let mut data = match document.and_then(|id| library.get(id)) {
+            Some(doc) => doc.data.clone(),
+            None => load_scratch(),
         };
 
-        if let Ok(serialized) = SessionStorage::get::<String>("bayes_component") {
-            if let Ok(loaded_data) = serde_json::from_str::<BayesData>(&serialized) {
-                data = loaded_data;
+        let mut history = BayesHistory::new();
+        if let Ok(serialized) = SessionStorage::get::<String>("bayes_history") {
+            if let Ok(loaded_history) = serde_json::from_str::<BayesHistory>(&serialized) {
+                history = loaded_history;
             }
         }
 
@@ -203,14 +404,16 @@ impl Component for BayesComponent {
             }
         }
 
+        prefs.fit_colors(data.hypotheses.len());
+
         let link = ctx.link().clone();
         let hashchange_listener = Closure::wrap(Box::new(move |_event: web_sys::Event| {
             let url = web_sys::window().unwrap().location().href().unwrap();
-            let encoded_data = url.split('#').last().unwrap_or("");
+            let encoded_data = url.rsplit('#').next().unwrap_or("");
             let decoded_data = decode_bayes_data(encoded_data);
 
             if let Ok(loaded_data) = decoded_data {
-                link.send_message(Msg::UpdateData(loaded_data));
+                link.send_message(Msg::UpdateData(Box::new(loaded_data)));
             }
         }) as Box<dyn FnMut(web_sys::Event)>);
 
@@ -222,15 +425,50 @@ impl Component for BayesComponent {
             )
             .unwrap();
 
-        Self {
+        let link = ctx.link().clone();
+        let keydown_listener = Closure::wrap(Box::new(move |event: KeyboardEvent| {
+            if !(event.ctrl_key() || event.meta_key()) {
+                return;
+            }
+            // Leave anything editable to its own undo.
+            let editable = event
+                .target()
+                .and_then(|target| target.dyn_into::<HtmlElement>().ok())
+                .is_some_and(|element| is_editable(&element));
+            if editable {
+                return;
+            }
+            match event.key().to_lowercase().as_str() {
+                "z" if event.shift_key() => link.send_message(Msg::Redo),
+                "z" => link.send_message(Msg::Undo),
+                "y" => link.send_message(Msg::Redo),
+                _ => return,
+            }
+            event.prevent_default();
+        }) as Box<dyn FnMut(KeyboardEvent)>);
+
+        web_sys::window()
+            .unwrap()
+            .add_event_listener_with_callback("keydown", keydown_listener.as_ref().unchecked_ref())
+            .unwrap();
+
+        let mut component = Self {
             data,
             onload: None,
-            error_message: None,
+            diagnostics: Vec::new(),
             prefs,
             show_link: false,
             link: None,
+            history,
+            library,
+            document,
+            predictions,
+            intervals: None,
             _hashchange_listener: Some(hashchange_listener),
-        }
+            _keydown_listener: Some(keydown_listener),
+        };
+        component.refresh_intervals();
+        component
     }
 
     fn view(&self, ctx: &yew::Context<Self>) -> Html {
@@ -239,6 +477,7 @@ impl Component for BayesComponent {
                 .callback(move |chance_msg: ChanceCallback| match chance_msg {
                     ChanceCallback::EditHypothesis(u, e, h) => Msg::Prior(u, e, h),
                     ChanceCallback::Delete(u) => Msg::DeleteHypothesis(u),
+                    ChanceCallback::Spread(u, s) => Msg::PriorSpread(u, s),
                 });
         let onchange_posterior = ctx.link().callback(move |_: ChanceCallback| Msg::Posterior);
 
@@ -249,9 +488,67 @@ impl Component for BayesComponent {
         let onclick_clear = ctx.link().callback(|_e: MouseEvent| Msg::Clear);
         let onclick_help = ctx.link().callback(|_e: MouseEvent| Msg::ToggleModal);
         let onclick_generate_link = ctx.link().callback(|_e: MouseEvent| Msg::GenerateLink);
+        let onclick_uncertainty = ctx.link().callback(|_e: MouseEvent| Msg::ToggleUncertainty);
+        let onclick_sort = ctx
+            .link()
+            .callback(|_e: MouseEvent| Msg::ToggleSortByInfluence);
+        let onclick_sensitivity = ctx.link().callback(|_e: MouseEvent| Msg::ToggleSensitivity);
+        let onclick_decision = ctx.link().callback(|_e: MouseEvent| Msg::ToggleDecision);
+        let onchange_decision =
+            ctx.link()
+                .callback(|decision_msg: DecisionCallback| match decision_msg {
+                    DecisionCallback::AddAction => Msg::AddAction,
+                    DecisionCallback::RenameAction(action_idx, name) => {
+                        Msg::RenameAction(action_idx, name)
+                    }
+                    DecisionCallback::DeleteAction(action_idx) => Msg::DeleteAction(action_idx),
+                    DecisionCallback::Utility(action_idx, hyp_idx, value) => {
+                        Msg::Utility(action_idx, hyp_idx, value)
+                    }
+                });
+        let onclick_calibration = ctx.link().callback(|_e: MouseEvent| Msg::ToggleCalibration);
+        let onchange_calibration =
+            ctx.link().callback(
+                |calibration_msg: CalibrationCallback| match calibration_msg {
+                    CalibrationCallback::Remove(idx) => Msg::RemovePrediction(idx),
+                    CalibrationCallback::Export => Msg::ExportPredictions,
+                },
+            );
+        let onchange_unit = ctx.link().callback(|e: Event| {
+            let select: web_sys::HtmlSelectElement = e.target_unchecked_into();
+            Msg::SetStrengthUnit(StrengthUnit::from_name(&select.value()).unwrap_or_default())
+        });
+        let onchange_format = ctx.link().callback(|e: Event| {
+            let select: web_sys::HtmlSelectElement = e.target_unchecked_into();
+            Msg::SetFileFormat(FileFormat::from_name(&select.value()).unwrap_or_default())
+        });
+        let onchange_frequency_view = ctx.link().callback(|e: Event| {
+            let select: web_sys::HtmlSelectElement = e.target_unchecked_into();
+            Msg::SetFrequencyView(match select.value().as_str() {
+                "icons" => FrequencyView::Icons,
+                "tree" => FrequencyView::Tree,
+                _ => FrequencyView::Hidden,
+            })
+        });
+        let onclick_trajectory = ctx.link().callback(|_e: MouseEvent| Msg::ToggleTrajectory);
+        let onclick_undo = ctx.link().callback(|_e: MouseEvent| Msg::Undo);
+        let onclick_redo = ctx.link().callback(|_e: MouseEvent| Msg::Redo);
+        let onchange_library =
+            ctx.link()
+                .callback(move |library_msg: LibraryCallback| match library_msg {
+                    LibraryCallback::Switch(id) => Msg::SwitchDocument(id),
+                    LibraryCallback::New => Msg::NewDocument,
+                    LibraryCallback::Rename(name) => Msg::RenameDocument(name),
+                    LibraryCallback::Duplicate => Msg::DuplicateDocument,
+                    LibraryCallback::Delete => Msg::DeleteDocument,
+                    LibraryCallback::Resolve(truth) => Msg::Resolve(truth),
+                });
+        let onmouseup = ctx.link().callback(|_e: MouseEvent| Msg::EndEdit);
+        let ontouchend = ctx.link().callback(|_e: TouchEvent| Msg::EndEdit);
 
         let toggle_modal = ctx.link().callback(|_| Msg::ToggleModal);
         let hide_share = ctx.link().callback(|_| Msg::HideShare);
+        let dismiss_diagnostics = ctx.link().callback(|_| Msg::DismissDiagnostics);
 
         let on_file_input_change = ctx.link().callback(|e: Event| {
             Msg::FileSelected(
@@ -268,11 +565,38 @@ impl Component for BayesComponent {
                     }
                     EvidenceCallback::LabelEdit(label) => Msg::EditEvidence(ev_idx, label),
                     EvidenceCallback::Delete => Msg::DeleteEvidence(ev_idx),
+                    EvidenceCallback::ToggleActive => Msg::ToggleEvidence(ev_idx),
+                    EvidenceCallback::TogglePlanned => Msg::TogglePlanned(ev_idx),
+                    EvidenceCallback::Spread(hyp_idx, spread) => {
+                        Msg::LikelihoodSpread(ev_idx, hyp_idx, spread)
+                    }
+                    EvidenceCallback::Kind(kind) => Msg::SetKind(ev_idx, kind),
+                    EvidenceCallback::Observation(value) => Msg::Observation(ev_idx, value),
+                    EvidenceCallback::Distribution(hyp_idx, dist) => {
+                        Msg::Distribution(ev_idx, hyp_idx, dist)
+                    }
+                    EvidenceCallback::Observe(outcome_idx) => {
+                        Msg::ObserveOutcome(ev_idx, outcome_idx)
+                    }
+                    EvidenceCallback::OutcomeLikelihood(hyp_idx, outcome_idx, p) => {
+                        Msg::OutcomeLikelihood(ev_idx, hyp_idx, outcome_idx, p)
+                    }
+                    EvidenceCallback::AddOutcome => Msg::AddOutcome(ev_idx),
+                    EvidenceCallback::RenameOutcome(outcome_idx, name) => {
+                        Msg::RenameOutcome(ev_idx, outcome_idx, name)
+                    }
+                    EvidenceCallback::DeleteOutcome(outcome_idx) => {
+                        Msg::DeleteOutcome(ev_idx, outcome_idx)
+                    }
+                    EvidenceCallback::EntryMode(mode) => Msg::EntryMode(ev_idx, mode),
+                    EvidenceCallback::Ratio(hyp_idx, ratio) => {
+                        Msg::LikelihoodRatio(ev_idx, hyp_idx, ratio)
+                    }
                 })
         };
 
         let prior_odds = self.data.prior_odds.clone();
-        let likelihoods = self.data.likelihoods.clone();
+        let likelihoods = self.data.effective_likelihoods();
 
         let onmousemove = move |e: MouseEvent| {
             let elements = document().get_elements_by_class_name("bart");
@@ -280,6 +604,10 @@ impl Component for BayesComponent {
             for i in 0..elements.length() {
                 if let Some(elem) = elements.item(i) {
                     let elem = elem.dyn_into::<HtmlElement>().unwrap();
+                    let ev_idx = elem
+                        .get_attribute("data-ev")
+                        .and_then(|ev| ev.parse::<usize>().ok())
+                        .unwrap_or(i as usize);
                     let elem_rect = elem.get_bounding_client_rect();
 
                     let above_cursor = (e.client_y() as f64) < elem_rect.top();
@@ -289,7 +617,7 @@ impl Component for BayesComponent {
                             false,
                             prior_odds.clone(),
                             likelihoods.clone(),
-                            i as usize,
+                            ev_idx,
                         );
                     } else {
                         update_bar_widths(
@@ -297,7 +625,7 @@ impl Component for BayesComponent {
                             true,
                             prior_odds.clone(),
                             likelihoods.clone(),
-                            i as usize,
+                            ev_idx,
                         );
                     }
                 }
@@ -313,44 +641,111 @@ impl Component for BayesComponent {
             .collect::<Vec<AttrValue>>();
         let hypotheses2 = hypotheses.clone();
 
-        let display_evidence = self.data.evidence.iter().enumerate().map(move |ev|
+        let effective_likelihoods = self.data.effective_likelihoods();
+        let influence = evidence_influence(&self.data);
+        let mut evidence_order: Vec<usize> = (0..self.data.evidence.len()).collect();
+        if self.prefs.sort_by_influence {
+            evidence_order.sort_by(|a, b| influence[*b].shift.total_cmp(&influence[*a].shift));
+        }
+        let display_evidence = evidence_order.into_iter().map(|ev_idx| (ev_idx, &self.data.evidence[ev_idx])).map(move |ev|
             html!{<EvidenceComponent
-                prior_odds={recalculate_to(self.data.prior_odds.clone(), self.data.likelihoods.clone() ,ev.0)}
+                key={ev.0}
+                index={ev.0}
+                influence={influence.get(ev.0).cloned()}
+                prior_odds={recalculate_to(self.data.prior_odds.clone(), effective_likelihoods.clone(), ev.0)}
                 hypotheses={hypotheses2.clone()}
                 label={ev.1.clone()}
                 onchange={&onchange_evidence(ev.0)}
                 likelihoods = {self.data.likelihoods[ev.0].clone()}
+                cumulative_log_odds={log_recalculate_to(&vec![1.0; hypotheses2.len()], &effective_likelihoods, ev.0 + 1)}
+                active={self.data.is_active(ev.0)}
+                planned={self.data.is_planned(ev.0)}
+                information={self.data.is_planned(ev.0).then(|| value_of_information(&self.data, ev.0))}
+                categorical={self.data.categorical(ev.0).cloned()}
+                continuous={self.data.continuous(ev.0).cloned()}
+                entry_mode={self.data.entry_mode(ev.0)}
+                unit={self.prefs.strength_unit}
+                spreads={self.prefs.show_uncertainty.then(|| (0..hypotheses2.len()).map(|hyp_idx| self.data.likelihood_spread(ev.0, hyp_idx)).collect::<Vec<_>>())}
                 last = {ev.0 == self.data.evidence.len() -1 }
                 color = {self.prefs.color.clone()}
                 />
             });
 
         html! {
-            <div class={format!("container len-{}", hypotheses.len())} onmousemove={onmousemove}>
+            <div class={format!("container len-{}", hypotheses.len())} onmousemove={onmousemove}
+                onmouseup={onmouseup} ontouchend={ontouchend}
+                style={format!("--cols: {}; --col-width: {}px;", hypotheses.len(), column_width(hypotheses.len()))}>
+                <style>{palette_css(self.prefs.color.len())}</style>
                 <nav class="menu">
                     <div class="title">
                     <a href="">{"bayescalc.io"}</a>
                     </div>
                     <button class="clear-session" onclick={onclick_help}>{"Help"}</button>
                     <button class="clear-session" onclick={onclick_clear}>{"Clear"}</button>
+                    <div class="undo-redo">
+                        <button onclick={onclick_undo} disabled={!self.history.can_undo()} title="Undo (Ctrl+Z)">{"Undo"}</button>
+                        <button onclick={onclick_redo} disabled={!self.history.can_redo()} title="Redo (Ctrl+Shift+Z)">{"Redo"}</button>
+                    </div>
                     <button class="clear-session" onclick={onclick_generate_link}>{"Link"}</button>
                     if self.link.is_some() {
                       <ShareComponent link={AttrValue::from(self.link.clone().unwrap())} show={self.show_link} on_close={hide_share}/>
                     }
+                    <button class="clear-session" onclick={onclick_uncertainty}>
+                        {if self.prefs.show_uncertainty { "Hide uncertainty" } else { "Uncertainty" }}
+                    </button>
+                    <button class="clear-session" onclick={onclick_sensitivity}>
+                        {if self.prefs.show_sensitivity { "Hide sensitivity" } else { "Sensitivity" }}
+                    </button>
+                    <button class="clear-session" onclick={onclick_sort}>
+                        {if self.prefs.sort_by_influence { "Original order" } else { "Sort by influence" }}
+                    </button>
+                    <button class="clear-session" onclick={onclick_decision}>
+                        {if self.prefs.show_decision { "Hide decision" } else { "Decision" }}
+                    </button>
+                    <button class="clear-session" onclick={onclick_trajectory}>
+                        {if self.prefs.show_trajectory { "Hide trajectory" } else { "Trajectory" }}
+                    </button>
+                    <button class="clear-session" onclick={onclick_calibration}>
+                        {if self.prefs.show_calibration { "Hide calibration" } else { "Calibration" }}
+                    </button>
+                    <select class="unit-picker" onchange={onchange_unit} title="Unit for evidence strength and log-odds">
+                        {for StrengthUnit::ALL.iter().map(|unit| html! {
+                            <option value={unit.name()} selected={*unit == self.prefs.strength_unit}>{format!("Units: {}", unit.name())}</option>
+                        })}
+                    </select>
+                    <select class="unit-picker" onchange={onchange_frequency_view} title="Show the posterior as counts of people">
+                        <option value="hidden" selected={self.prefs.frequency_view == FrequencyView::Hidden}>{"No frequency view"}</option>
+                        <option value="icons" selected={self.prefs.frequency_view == FrequencyView::Icons}>{"Icon array"}</option>
+                        <option value="tree" selected={self.prefs.frequency_view == FrequencyView::Tree}>{"Frequency tree"}</option>
+                    </select>
+                    <select class="unit-picker" onchange={onchange_format} title="Format for Export, and for loading files whose extension doesn't say">
+                        {for FileFormat::ALL.iter().map(|format| html! {
+                            <option value={format.name()} selected={*format == self.prefs.file_format}>{format!("Format: {}", format.name())}</option>
+                        })}
+                    </select>
                     <button class="export-markdown" onclick={onclick_export}>{"Export"}</button>
 
                     <label class="dropzone" for="fileInput">
                         <span>{"Load"}</span>
-                        <input type="file" accept=".md" id="fileInput" onchange={on_file_input_change} style="display: none;" />
+                        <input type="file" accept={FileFormat::ALL.iter().flat_map(|format| format.accepts()).copied().collect::<Vec<_>>().join(",")} id="fileInput" onchange={on_file_input_change} style="display: none;" />
                      </label>
 
-                    {if let Some(ref error_message) = self.error_message {
-                        html!{ <div class="invalid">{error_message}</div> }
-                    } else {
-                        html!{}
-                    }}
+                    <LibraryComponent
+                        documents={self.library.recent().into_iter().cloned().collect::<Vec<_>>()}
+                        current={self.document}
+                        hypotheses={hypotheses.clone()}
+                        resolved={self.document.and_then(|id| self.predictions.for_document(id)).map(|prediction| prediction.truth)}
+                        onchange={onchange_library}
+                    />
+
                 </nav>
 
+                {if !self.diagnostics.is_empty() {
+                    html!{ <DiagnosticsComponent diagnostics={self.diagnostics.clone()} on_close={dismiss_diagnostics} /> }
+                } else {
+                    html!{}
+                }}
+
                 <ModalComponent
                 is_open={self.prefs.is_modal_open}
                 on_close={toggle_modal}
@@ -364,7 +759,8 @@ impl Component for BayesComponent {
                         <div class="center">
                             <ChanceComponent onchange={onchange_prior} force_chance={Some(self.data.prior_odds.clone())}
                                 hypotheses={hypotheses.clone()} onadd_hypothesis={onchange_add_hypothesis} kind={Kind::Prior}
-                                color = {self.prefs.color.clone()}/>
+                                color = {self.prefs.color.clone()}
+                                spreads={self.prefs.show_uncertainty.then(|| (0..hypotheses.len()).map(|hyp_idx| self.data.prior_spread(hyp_idx)).collect::<Vec<_>>())}/>
                         </div>
                     </div>
 
@@ -386,40 +782,91 @@ impl Component for BayesComponent {
                         </div>
                         <div class="center">
                             <ChanceComponent onchange={onchange_posterior} force_chance={Some(self.data.posterior_odds.clone())}
-                                hypotheses={hypotheses.clone()} kind={Kind::Posterior} color = {self.prefs.color.clone()}/>
+                                hypotheses={hypotheses.clone()} kind={Kind::Posterior} color = {self.prefs.color.clone()}
+                                intervals={self.intervals.as_ref().and_then(|(_, intervals)| intervals.clone())}/>
+                            if ruled_out(&self.data.posterior_odds) {
+                                <div class="invalid ruled-out">
+                                    {"Every hypothesis has been ruled out: each one has a 0% likelihood for some piece of evidence."}
+                                </div>
+                            }
+                            <div class="posterior-log-odds" title="Posterior log-odds of each hypothesis against all the others">
+                                {for log_odds_in_db(self.data.posterior_odds.clone()).into_iter().enumerate().map(|(hyp_idx, db)| html! {
+                                    <span class={format!("e{}", self.prefs.color[hyp_idx])}>{self.prefs.strength_unit.format(db)}</span>
+                                })}
+                            </div>
                         </div>
                     </div>
+
+                    if self.prefs.show_trajectory {
+                        <TrajectoryComponent
+                            prior_odds={self.data.prior_odds.clone()}
+                            likelihoods={self.data.effective_likelihoods()}
+                            hypotheses={hypotheses.clone()}
+                            evidence={self.data.evidence.iter().cloned().map(AttrValue::from).collect::<Vec<_>>()}
+                            counted={(0..self.data.evidence.len()).map(|ev_idx| self.data.counts(ev_idx)).collect::<Vec<_>>()}
+                            color={self.prefs.color.clone()}
+                            unit={self.prefs.strength_unit}
+                        />
+                    }
+
+                    if self.prefs.frequency_view != FrequencyView::Hidden {
+                        <FrequencyComponent
+                            view={self.prefs.frequency_view}
+                            steps={natural_frequencies(&self.data, POPULATION)}
+                            hypotheses={hypotheses.clone()}
+                            evidence={self.data.evidence.iter().cloned().map(AttrValue::from).collect::<Vec<_>>()}
+                            color={self.prefs.color.clone()}
+                        />
+                    }
+
+                    if self.prefs.show_sensitivity && !ruled_out(&self.data.posterior_odds) {
+                        <SensitivityComponent
+                            sensitivities={sensitivities(&self.data, SENSITIVITY_DELTA)}
+                            posterior={self.data.posterior_odds.clone()}
+                            hypotheses={hypotheses.clone()}
+                            evidence={self.data.evidence.iter().cloned().map(AttrValue::from).collect::<Vec<_>>()}
+                            color={self.prefs.color.clone()}
+                            delta={SENSITIVITY_DELTA}
+                        />
+                    }
+
+                    if self.prefs.show_decision {
+                        <DecisionComponent
+                            actions={self.data.actions.clone()}
+                            utilities={self.data.utilities.clone()}
+                            posterior={self.data.posterior_odds.clone()}
+                            hypotheses={hypotheses.clone()}
+                            color={self.prefs.color.clone()}
+                            onchange={onchange_decision}
+                        />
+                    }
+
+                    if self.prefs.show_calibration {
+                        <CalibrationComponent log={self.predictions.clone()} onchange={onchange_calibration} />
+                    }
                 </div>
             </div>
         }
     }
 
     fn update(&mut self, ctx: &yew::Context<Self>, msg: Self::Message) -> bool {
+        let before = edit_key(&msg).map(|key| (self.data.clone(), key));
         match msg {
             Msg::AddHypothesis => {
-                if self.data.hypotheses.len() == 5 {
-                    return false;
-                }
-                self.data.hypotheses.push(format!(
-                    "Hypothesis {}",
-                    (b'A' + self.data.hypotheses.len() as u8) as char
-                ));
-                self.data.prior_odds.push(1.0);
-                self.data.posterior_odds.push(1.0);
-                for ev_idx in 0..self.data.evidence.len() {
-                    self.data.likelihoods[ev_idx].push(0.5);
-                }
+                self.data.add_hypothesis(
+                    format!(
+                        "Hypothesis {}",
+                        hypothesis_letters(self.data.hypotheses.len())
+                    ),
+                    1.0,
+                );
                 ctx.link().send_message(Msg::ClearUrl);
             }
             Msg::DeleteHypothesis(hyp_idx) => {
-                self.data.hypotheses.remove(hyp_idx);
-                self.data.prior_odds.remove(hyp_idx);
-                self.data.posterior_odds.remove(hyp_idx);
-                for ev_idx in 0..self.data.evidence.len() {
-                    self.data.likelihoods[ev_idx].remove(hyp_idx);
-                }
+                self.data.remove_hypothesis(hyp_idx);
                 let removed_color = self.prefs.color.remove(hyp_idx);
                 self.prefs.color.push(removed_color);
+                ctx.link().send_message(Msg::ClearUrl);
             }
             Msg::Prior(idx, val, hyp) => {
                 self.data.hypotheses[idx] = hyp[idx].to_string();
@@ -429,68 +876,202 @@ impl Component for BayesComponent {
             Msg::Posterior => {}
             Msg::AddEvidence => {
                 self.data
-                    .evidence
-                    .push(format!("Evidence {}", self.data.evidence.len() + 1));
-                self.data
-                    .likelihoods
-                    .push(vec![0.5; self.data.hypotheses.len()]);
+                    .add_evidence(format!("Evidence {}", self.data.evidence.len() + 1));
                 ctx.link().send_message(Msg::ClearUrl);
             }
             Msg::DeleteEvidence(ev_idx) => {
-                self.data.evidence.remove(ev_idx);
-                self.data.likelihoods.remove(ev_idx);
+                self.data.remove_evidence(ev_idx);
+                ctx.link().send_message(Msg::ClearUrl);
+            }
+            Msg::PriorSpread(hyp_idx, spread) => {
+                self.data.set_prior_spread(hyp_idx, spread);
+                ctx.link().send_message(Msg::ClearUrl);
+            }
+            Msg::LikelihoodSpread(ev_idx, hyp_idx, spread) => {
+                self.data.set_likelihood_spread(ev_idx, hyp_idx, spread);
+                ctx.link().send_message(Msg::ClearUrl);
+            }
+            Msg::ToggleUncertainty => {
+                self.prefs.show_uncertainty = !self.prefs.show_uncertainty;
+                save_prefs(&self.prefs);
+            }
+            Msg::ToggleSensitivity => {
+                self.prefs.show_sensitivity = !self.prefs.show_sensitivity;
+                save_prefs(&self.prefs);
+            }
+            Msg::ToggleSortByInfluence => {
+                self.prefs.sort_by_influence = !self.prefs.sort_by_influence;
+                save_prefs(&self.prefs);
+            }
+            Msg::ToggleDecision => {
+                self.prefs.show_decision = !self.prefs.show_decision;
+                save_prefs(&self.prefs);
+            }
+            Msg::SetStrengthUnit(unit) => {
+                self.prefs.strength_unit = unit;
+                save_prefs(&self.prefs);
+            }
+            Msg::SetFileFormat(format) => {
+                self.prefs.file_format = format;
+                save_prefs(&self.prefs);
+            }
+            Msg::SetFrequencyView(view) => {
+                self.prefs.frequency_view = view;
+                save_prefs(&self.prefs);
+            }
+            Msg::ToggleTrajectory => {
+                self.prefs.show_trajectory = !self.prefs.show_trajectory;
+                save_prefs(&self.prefs);
+            }
+            Msg::ToggleCalibration => {
+                self.prefs.show_calibration = !self.prefs.show_calibration;
+                save_prefs(&self.prefs);
+            }
+            Msg::AddAction => {
+                self.data
+                    .add_action(format!("Action {}", self.data.actions.len() + 1));
+                ctx.link().send_message(Msg::ClearUrl);
+            }
+            Msg::RenameAction(action_idx, name) => {
+                self.data.actions[action_idx] = name;
+                ctx.link().send_message(Msg::ClearUrl);
+            }
+            Msg::DeleteAction(action_idx) => {
+                self.data.remove_action(action_idx);
+                ctx.link().send_message(Msg::ClearUrl);
+            }
+            Msg::Utility(action_idx, hyp_idx, value) => {
+                self.data.utilities[action_idx][hyp_idx] = value;
+                ctx.link().send_message(Msg::ClearUrl);
+            }
+            Msg::ToggleEvidence(ev_idx) => {
+                let active = self.data.is_active(ev_idx);
+                self.data.set_active(ev_idx, !active);
+                ctx.link().send_message(Msg::ClearUrl);
+            }
+            Msg::SetKind(ev_idx, kind) => {
+                let kind = match kind {
+                    KindChoice::Binary => EvidenceKind::Binary,
+                    KindChoice::Categorical => EvidenceKind::Categorical(Categorical::from_binary(
+                        &self.data.likelihoods[ev_idx],
+                    )),
+                    KindChoice::Continuous => {
+                        EvidenceKind::Continuous(Continuous::new(self.data.hypotheses.len()))
+                    }
+                };
+                self.data.set_kind(ev_idx, kind);
+                ctx.link().send_message(Msg::ClearUrl);
+            }
+            Msg::Observation(ev_idx, value) => {
+                self.data.edit_continuous(ev_idx, |continuous| {
+                    continuous.observed = value;
+                });
+                ctx.link().send_message(Msg::ClearUrl);
+            }
+            Msg::Distribution(ev_idx, hyp_idx, dist) => {
+                self.data.edit_continuous(ev_idx, |continuous| {
+                    continuous.distributions[hyp_idx] = dist;
+                });
+                ctx.link().send_message(Msg::ClearUrl);
+            }
+            Msg::ObserveOutcome(ev_idx, outcome_idx) => {
+                self.data.edit_categorical(ev_idx, |categorical| {
+                    categorical.observed = outcome_idx;
+                });
+                ctx.link().send_message(Msg::ClearUrl);
+            }
+            Msg::OutcomeLikelihood(ev_idx, hyp_idx, outcome_idx, p) => {
+                self.data.edit_categorical(ev_idx, |categorical| {
+                    categorical.set_likelihood(hyp_idx, outcome_idx, p);
+                });
+                ctx.link().send_message(Msg::ClearUrl);
+            }
+            Msg::AddOutcome(ev_idx) => {
+                self.data.edit_categorical(ev_idx, |categorical| {
+                    let name = format!("Outcome {}", categorical.outcomes.len() + 1);
+                    categorical.add_outcome(name);
+                });
+                ctx.link().send_message(Msg::ClearUrl);
+            }
+            Msg::RenameOutcome(ev_idx, outcome_idx, name) => {
+                self.data.edit_categorical(ev_idx, |categorical| {
+                    categorical.outcomes[outcome_idx] = name;
+                });
+                ctx.link().send_message(Msg::ClearUrl);
+            }
+            Msg::DeleteOutcome(ev_idx, outcome_idx) => {
+                self.data.edit_categorical(ev_idx, |categorical| {
+                    categorical.remove_outcome(outcome_idx);
+                });
+                ctx.link().send_message(Msg::ClearUrl);
+            }
+            Msg::TogglePlanned(ev_idx) => {
+                let planned = self.data.is_planned(ev_idx);
+                self.data.set_planned(ev_idx, !planned);
                 ctx.link().send_message(Msg::ClearUrl);
             }
             Msg::Evidence(ev_idx, hyp_idx, new_odds) => {
                 self.data.likelihoods[ev_idx][hyp_idx] = new_odds;
                 ctx.link().send_message(Msg::ClearUrl);
             }
+            Msg::LikelihoodRatio(ev_idx, hyp_idx, ratio) => {
+                self.data.set_likelihood_ratio(ev_idx, hyp_idx, ratio);
+                ctx.link().send_message(Msg::ClearUrl);
+            }
+            Msg::EntryMode(ev_idx, mode) => {
+                self.data.set_entry_mode(ev_idx, mode);
+                ctx.link().send_message(Msg::ClearUrl);
+            }
             Msg::EditEvidence(ev_idx, new_evidence) => {
                 self.data.evidence[ev_idx] = new_evidence;
                 ctx.link().send_message(Msg::ClearUrl);
             }
             Msg::Clear => {
-                SessionStorage::delete("bayes_component");
+                if self.document.is_none() {
+                    SessionStorage::delete("bayes_component");
+                }
                 ctx.link().send_message(Msg::ClearUrl);
 
-                self.data = BayesData {
-                    hypotheses: vec!["Hypothesis A".to_string(), "Hypothesis B".to_string()],
-                    prior_odds: vec![50.0, 50.0],
-                    posterior_odds: vec![50.0, 50.0],
-                    evidence: vec!["Evidence 1".to_string()],
-                    likelihoods: vec![vec![0.5, 0.5]],
-                };
+                self.data = BayesData::default();
             }
             Msg::Export => {
-                export_to_markdown(&self.data);
+                export_file(&self.data, self.prefs.file_format, self.prefs.strength_unit);
             }
             Msg::FileSelected(file) => {
+                let file = file.unwrap();
+                let name = file.name();
                 let link = ctx.link().clone();
                 let reader = FileReader::new().unwrap();
                 let onload = Closure::wrap(Box::new(move |event: Event| {
                     let file_reader: FileReader = event.target().unwrap().unchecked_into();
                     let content = file_reader.result().unwrap().as_string().unwrap();
-                    link.send_message(Msg::FileContent(content));
+                    link.send_message(Msg::FileContent(name.clone(), content));
                 }) as Box<dyn FnMut(Event)>);
 
                 reader.set_onload(Some(onload.as_ref().unchecked_ref()));
-                reader.read_as_text(&file.unwrap()).unwrap();
+                reader.read_as_text(&file).unwrap();
                 self.onload = Some(onload);
             }
-            Msg::FileContent(content) => match parse_markdown(&content) {
-                Ok(parsed_data) => {
-                    self.data = parsed_data;
-                    self.error_message = None;
+            Msg::FileContent(name, content) => match FileFormat::from_file_name(&name)
+                .unwrap_or(self.prefs.file_format)
+                .parse(&content)
+            {
+                Ok(imported) => {
+                    self.data = imported.data;
+                    self.diagnostics = imported.warnings;
                     ctx.link().send_message(Msg::ClearUrl);
                 }
                 Err(e) => {
-                    self.error_message = Some(format!("Error: Invalid file format. {:?}", e));
+                    self.diagnostics = e.diagnostics;
                 }
             },
             Msg::ToggleModal => {
                 self.prefs.is_modal_open = !self.prefs.is_modal_open;
                 save_prefs(&self.prefs)
             }
+            Msg::DismissDiagnostics => {
+                self.diagnostics.clear();
+            }
             Msg::HideShare => {
                 self.show_link = false;
             }
@@ -516,11 +1097,98 @@ impl Component for BayesComponent {
                 self.link = Some(new_url);
             }
             Msg::UpdateData(new_data) => {
-                self.data = new_data;
+                self.data = *new_data;
+            }
+            Msg::Undo => {
+                if let Some(previous) = self.history.undo(self.data.clone()) {
+                    self.data = previous;
+                    save_history(&self.history);
+                    ctx.link().send_message(Msg::ClearUrl);
+                }
+            }
+            Msg::Redo => {
+                if let Some(next) = self.history.redo(self.data.clone()) {
+                    self.data = next;
+                    save_history(&self.history);
+                    ctx.link().send_message(Msg::ClearUrl);
+                }
+            }
+            Msg::EndEdit => {
+                self.history.break_coalescing();
+                return false;
+            }
+            Msg::SwitchDocument(id) => {
+                self.switch_document(id);
+                ctx.link().send_message(Msg::ClearUrl);
+            }
+            Msg::NewDocument => {
+                let id = self
+                    .library
+                    .create("Untitled", BayesData::default(), js_sys::Date::now());
+                save_library(&self.library);
+                self.switch_document(Some(id));
+                ctx.link().send_message(Msg::ClearUrl);
+            }
+            Msg::RenameDocument(name) => {
+                if let Some(id) = self.document {
+                    self.library.rename(id, &name, js_sys::Date::now());
+                    save_library(&self.library);
+                }
+            }
+            Msg::DuplicateDocument => {
+                let id = match self.document {
+                    Some(id) => self.library.duplicate(id, js_sys::Date::now()),
+                    None => Some(self.library.create(
+                        "Scratch",
+                        self.data.clone(),
+                        js_sys::Date::now(),
+                    )),
+                };
+                save_library(&self.library);
+                self.switch_document(id);
+                ctx.link().send_message(Msg::ClearUrl);
+            }
+            Msg::DeleteDocument => {
+                if let Some(id) = self.document {
+                    self.library.delete(id);
+                    save_library(&self.library);
+                    self.switch_document(None);
+                    ctx.link().send_message(Msg::ClearUrl);
+                }
+            }
+            Msg::Resolve(truth) => {
+                if let Some(id) = self.document {
+                    match truth {
+                        Some(truth) => {
+                            let name = self
+                                .library
+                                .get(id)
+                                .map(|doc| doc.name.clone())
+                                .unwrap_or_default();
+                            self.predictions.resolve(Prediction {
+                                document: Some(id),
+                                name,
+                                hypotheses: self.data.hypotheses.clone(),
+                                posterior: self.data.posterior_odds.clone(),
+                                truth,
+                                resolved: js_sys::Date::now(),
+                            });
+                        }
+                        None => self.predictions.unresolve(id),
+                    }
+                    save_predictions(&self.predictions);
+                }
+            }
+            Msg::RemovePrediction(idx) => {
+                self.predictions.remove(idx);
+                save_predictions(&self.predictions);
+            }
+            Msg::ExportPredictions => {
+                export_predictions_csv(&self.predictions);
             }
             Msg::ClearUrl => {
                 let url = web_sys::window().unwrap().location().href().unwrap();
-                if url.contains('#') && url.split('#').last().is_some() {
+                if url.contains('#') && url.rsplit('#').next().is_some() {
                     let window = web_sys::window().unwrap();
                     let history = window.history().unwrap();
                     history
@@ -533,11 +1201,65 @@ impl Component for BayesComponent {
                 self.link = None;
             }
         }
-        self.data.posterior_odds =
-            recalculate(self.data.prior_odds.clone(), self.data.likelihoods.clone());
+        self.data.posterior_odds = recalculate(
+            self.data.prior_odds.clone(),
+            self.data.effective_likelihoods(),
+        );
         self.data.posterior_odds = percentize(self.data.posterior_odds.clone());
+        self.refresh_intervals();
+
+        if let Some((before, key)) = before {
+            // Saving the whole history is slow, so a slider being dragged only
+            // saves it as the drag starts.
+            if before != self.data && self.history.record(before, key) {
+                save_history(&self.history);
+            }
+        }
 
-        save_data(&self.data);
+        if self.prefs.color.len() < self.data.hypotheses.len() {
+            self.prefs.fit_colors(self.data.hypotheses.len());
+            save_prefs(&self.prefs);
+        }
+        match self.document {
+            Some(id) => {
+                if self
+                    .library
+                    .get(id)
+                    .is_some_and(|doc| doc.data != self.data)
+                {
+                    self.library.update(id, &self.data, js_sys::Date::now());
+                    save_library(&self.library);
+                }
+            }
+            None => save_data(&self.data),
+        }
         true
     }
 }
+
+impl BayesComponent {
+    /// Redraws the posterior intervals if uncertainty is shown and the data
+    /// has changed since they were drawn.
+    fn refresh_intervals(&mut self) {
+        if !self.prefs.show_uncertainty {
+            self.intervals = None;
+        } else if self.intervals.as_ref().map(|(drawn_from, _)| drawn_from) != Some(&self.data) {
+            let intervals = posterior_intervals(&self.data, INTERVAL_SAMPLES, INTERVAL_LEVEL);
+            self.intervals = Some((self.data.clone(), intervals));
+        }
+    }
+
+    /// Makes `id` (or the scratch document, for `None`) the one being edited.
+    /// Each document gets a fresh undo history.
+    fn switch_document(&mut self, id: Option<u64>) {
+        let id = id.filter(|id| self.library.get(*id).is_some());
+        self.data = match id.and_then(|id| self.library.get(id)) {
+            Some(doc) => doc.data.clone(),
+            None => load_scratch(),
+        };
+        self.document = id;
+        save_document(id);
+        self.history = BayesHistory::new();
+        save_history(&self.history);
+    }
+}
@@ -0,0 +1,150 @@
+// calibration_component.rs
+use bayes_core::PredictionLog;
+use wasm_bindgen::JsValue;
+use yew::prelude::*;
+
+const CALIBRATION_BINS: usize = 10;
+const PLOT_SIZE: f64 = 200.0;
+
+#[derive(Properties, PartialEq)]
+pub struct CalibrationProps {
+    pub log: PredictionLog,
+    pub onchange: Callback<CalibrationCallback>,
+}
+
+pub enum CalibrationCallback {
+    Remove(usize),
+    Export,
+}
+
+pub enum Msg {
+    Remove(usize),
+    Export,
+}
+
+pub struct CalibrationComponent {}
+
+fn format_date(ms: f64) -> String {
+    js_sys::Date::new(&JsValue::from_f64(ms))
+        .to_locale_date_string("default", &JsValue::UNDEFINED)
+        .into()
+}
+
+fn format_score(score: Option<f64>) -> String {
+    match score {
+        Some(score) if score == f64::NEG_INFINITY => "−∞".to_string(),
+        Some(score) => format!("{:.3}", score),
+        None => "–".to_string(),
+    }
+}
+
+/// Forecast probability against observed frequency, with the diagonal for
+/// perfect calibration. Dot area follows the number of forecasts in each bin.
+fn calibration_plot(log: &PredictionLog) -> Html {
+    let bins = log.calibration(CALIBRATION_BINS);
+    let max_count = bins.iter().map(|bin| bin.count).max().unwrap_or(1) as f64;
+    let dots = bins.iter().map(|bin| {
+        let radius = 2.0 + 6.0 * (bin.count as f64 / max_count).sqrt();
+        html! {
+            <circle class="calibration-dot" cx={(PLOT_SIZE * bin.forecast).to_string()}
+                cy={(PLOT_SIZE * (1.0 - bin.frequency)).to_string()} r={radius.to_string()}>
+                <title>{format!(
+                    "{:.0}–{:.0}%: forecast {:.1}%, true {:.1}% of {} times",
+                    100.0 * bin.low, 100.0 * bin.high, 100.0 * bin.forecast, 100.0 * bin.frequency, bin.count
+                )}</title>
+            </circle>
+        }
+    });
+    let curve: Vec<String> = bins
+        .iter()
+        .map(|bin| {
+            format!(
+                "{:.1},{:.1}",
+                PLOT_SIZE * bin.forecast,
+                PLOT_SIZE * (1.0 - bin.frequency)
+            )
+        })
+        .collect();
+    html! {
+        <svg class="calibration-plot" width={PLOT_SIZE.to_string()} height={PLOT_SIZE.to_string()}>
+            <rect class="calibration-frame" x="0" y="0" width={PLOT_SIZE.to_string()} height={PLOT_SIZE.to_string()} />
+            <line class="calibration-diagonal" x1="0" y1={PLOT_SIZE.to_string()} x2={PLOT_SIZE.to_string()} y2="0" />
+            if curve.len() > 1 {
+                <path class="calibration-curve" d={format!("M{}", curve.join(" L"))} />
+            }
+            {for dots}
+        </svg>
+    }
+}
+
+impl Component for CalibrationComponent {
+    type Message = Msg;
+    type Properties = CalibrationProps;
+
+    fn create(_ctx: &yew::Context<Self>) -> Self {
+        Self {}
+    }
+
+    fn update(&mut self, ctx: &yew::Context<Self>, msg: Self::Message) -> bool {
+        let onchange = ctx.props().onchange.clone();
+        match msg {
+            Msg::Remove(idx) => onchange.emit(CalibrationCallback::Remove(idx)),
+            Msg::Export => onchange.emit(CalibrationCallback::Export),
+        }
+        false
+    }
+
+    fn view(&self, ctx: &yew::Context<Self>) -> Html {
+        let log = &ctx.props().log;
+        if log.predictions.is_empty() {
+            return html! {
+                <div class="calibration">
+                    {"No resolved analyses yet. Save an analysis to the library, then resolve it once you know which hypothesis was true."}
+                </div>
+            };
+        }
+        let onclick_export = ctx.link().callback(|_e: MouseEvent| Msg::Export);
+
+        let rows = log.predictions.iter().enumerate().rev().map(|(idx, prediction)| {
+            let onclick_remove = ctx.link().callback(move |_e: MouseEvent| Msg::Remove(idx));
+            let probability = prediction
+                .probabilities()
+                .get(prediction.truth)
+                .copied()
+                .unwrap_or(0.0);
+            html! {
+                <tr>
+                    <td>{prediction.name.clone()}</td>
+                    <td>{format_date(prediction.resolved)}</td>
+                    <td>{prediction.hypotheses.get(prediction.truth).cloned().unwrap_or_default()}</td>
+                    <td>{format!("{:.1}%", 100.0 * probability)}</td>
+                    <td>{format!("{:.3}", prediction.brier_score())}</td>
+                    <td><button class="remove-prediction" onclick={onclick_remove} title="Remove from the log">{"✕"}</button></td>
+                </tr>
+            }
+        });
+
+        html! {
+            <div class="calibration">
+                <div class="calibration-scores">
+                    <div>{format!("{} resolved", log.predictions.len())}</div>
+                    <div title="Mean squared error of the posterior; 0 is perfect">
+                        {format!("Brier score {}", format_score(log.brier_score()))}
+                    </div>
+                    <div title="Mean natural log of the probability given to the truth; 0 is perfect">
+                        {format!("Log score {}", format_score(log.log_score()))}
+                    </div>
+                    <button onclick={onclick_export}>{"Export CSV"}</button>
+                </div>
+                {calibration_plot(log)}
+                <table class="predictions">
+                    <tr>
+                        <th>{"Analysis"}</th><th>{"Resolved"}</th><th>{"True"}</th>
+                        <th>{"P(true)"}</th><th>{"Brier"}</th><th></th>
+                    </tr>
+                    {for rows}
+                </table>
+            </div>
+        }
+    }
+}
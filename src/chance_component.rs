@@ -1,17 +1,15 @@
 // chance_component.rs
 use crate::label_component::LabelCallback;
+use crate::layout::column_width;
+use crate::spread_component::SpreadComponent;
 use crate::LabelComponent;
 use crate::NumComponent;
+use bayes_core::{percentize, Interval, Spread};
 use is_close::all_close;
 use web_sys::HtmlInputElement;
 use yew::prelude::*;
 use yew::virtual_dom::AttrValue;
 
-pub fn percentize(odds: Vec<f64>) -> Vec<f64> {
-    let total = odds.iter().sum::<f64>();
-    odds.iter().map(|x| 100.0 * x / total).collect()
-}
-
 fn pair_sum(idx: usize, odds: &[f64]) -> f64 {
     odds[idx] + odds[idx + 1]
 }
@@ -23,6 +21,7 @@ pub enum Msg {
     AddHypothesis,
     EditHypothesis(usize, String),
     Delete(usize),
+    Spread(usize, Option<Spread>),
     DoNothing,
 }
 
@@ -43,11 +42,18 @@ pub struct ChanceProps {
     #[prop_or(Kind::Prior)]
     pub kind: Kind,
     pub color: Vec<usize>,
+    /// Uncertainty inputs for each prior, shown only when given.
+    #[prop_or(None)]
+    pub spreads: Option<Vec<Option<Spread>>>,
+    /// Monte Carlo credible intervals for each posterior, shown only when given.
+    #[prop_or(None)]
+    pub intervals: Option<Vec<Interval>>,
 }
 
 pub enum ChanceCallback {
     Delete(usize),
     EditHypothesis(usize, Vec<f64>, Vec<AttrValue>),
+    Spread(usize, Option<Spread>),
 }
 
 pub struct ChanceComponent {
@@ -118,7 +124,7 @@ impl Component for ChanceComponent {
         let display_hypotheses = ctx.props().hypotheses.iter().enumerate().map(|(idx, hyp)| {
             html! {
                 <LabelComponent
-                class={AttrValue::from(format!("b{} c{} hyp", ctx.props().color[idx],idx))}
+                class={AttrValue::from(format!("b{} hyp", ctx.props().color[idx]))}
                 placeholder={AttrValue::from(hyp.clone())}
                 onchange={ctx.link().callback(move |label_change: LabelCallback| match label_change {
                     LabelCallback::Delete => Msg::Delete(idx),
@@ -132,7 +138,7 @@ impl Component for ChanceComponent {
 
         let display_odds = ctx.props().hypotheses.iter().enumerate().map(|(idx, _)| {
             html! {
-                <div class={format!("chance-label b{} c{}", ctx.props().color[idx], idx)}>
+                <div class={format!("chance-label b{}", ctx.props().color[idx])}>
                     <NumComponent min_value={0.0} max_value={None}
                     force_value={self.force_odds[idx]} class={AttrValue::from("odds")}
                     placeholder={AttrValue::from("1")} onchange={ctx.link().callback(move |odds: f64| Msg::Odds(idx, odds))}
@@ -140,10 +146,22 @@ impl Component for ChanceComponent {
                     <div class="percent">
                         <button class={is_percent} onclick={ctx.link().callback(move |_e: MouseEvent| Msg::Percentize)}>{"%"}</button>
                     </div>
+                    if let Some(spreads) = &ctx.props().spreads {
+                        <SpreadComponent spread={spreads.get(idx).copied().flatten()} scale={1.0}
+                            onchange={ctx.link().callback(move |spread: Option<Spread>| Msg::Spread(idx, spread))}/>
+                    }
+                    if let Some(interval) = ctx.props().intervals.as_ref().and_then(|intervals| intervals.get(idx)) {
+                        <div class="interval-label">
+                            {format!("median {:.1}% ({:.1}–{:.1}%)", interval.median, interval.low, interval.high)}
+                        </div>
+                    }
                 </div>
             }
         });
 
+        let cols = ctx.props().hypotheses.len();
+        let col_width = column_width(cols);
+
         let display_bar = ctx.props().hypotheses.iter().enumerate().map(|(idx, _)| {
             let onslide = {
                 let percents = percents.clone();
@@ -167,14 +185,14 @@ impl Component for ChanceComponent {
             html!{
                 <>
                 if ctx.props().kind == Kind::Prior {
-                    <div class={format!("b{} c{}", ctx.props().color[idx], idx)} style={format!("width:{}%", percents[idx])}>
+                    <div class={format!("b{}", ctx.props().color[idx])} style={format!("width:{}%", percents[idx])}>
                         if idx < ctx.props().hypotheses.len() - 1 {
                             <input type="range" min=0.0
                             max={100.0}
                             step={0.1}
                             value={AttrValue::from((percents[idx]/pair_sum(idx, &percents)*100.0).to_string())}
                             class="prior-slider" oninput={onslide(idx)}
-                            style={format!("width: {}px", pair_sum(idx, &percents)*2.0 *(ctx.props().hypotheses.len() as f64))}
+                            style={format!("width: {}px", pair_sum(idx, &percents) * 0.01 * (col_width * cols) as f64)}
                             />
                         }
                     </div>
@@ -182,16 +200,28 @@ impl Component for ChanceComponent {
                         <div class="triangle-bot"></div>
                     }
                 } else {
-                    <div class={format!("b{} c{}", ctx.props().color[idx], idx)} style={format!("width:{}%", percents[idx])}></div>
+                    <div class={format!("b{}", ctx.props().color[idx])} style={format!("width:{}%", percents[idx])}></div>
                 }
                 </>
             }
         });
 
-        let cols = ctx.props().hypotheses.len();
-        let col_str = format!("{}px", cols * 100);
+        let display_intervals = ctx.props().intervals.iter().flatten().enumerate().map(|(idx, interval)| {
+            html! {
+                <div class="interval-track" style={format!("width:{}px", col_width * cols)}>
+                    <div class={format!("interval b{}", ctx.props().color[idx])}
+                        style={format!("left:{}%; width:{}%", interval.low, interval.high - interval.low)}></div>
+                    <div class="interval-median" style={format!("left:{}%", interval.median)}></div>
+                </div>
+            }
+        });
 
-        let style = format!("display: grid; width: {};", col_str);
+        let style = format!(
+            "display: grid; grid-template-columns: repeat({}, {}px); width: {}px;",
+            cols,
+            col_width,
+            cols * col_width
+        );
 
         html! {
             <div style="display:flex">
@@ -201,12 +231,13 @@ impl Component for ChanceComponent {
             {for display_odds}
             </div>
 
-            <div class="prior-bar" style={format!("width:{}px",200*cols)}>
+            <div class="prior-bar" style={format!("width:{}px",col_width*cols)}>
 
             {for display_bar}
             </div>
+            {for display_intervals}
             </div>
-            if ctx.props().kind == Kind::Prior && ctx.props().hypotheses.len() < 5 {
+            if ctx.props().kind == Kind::Prior {
                 <button class="add-hypothesis" onclick={onclick_add_hypothesis} >{"+"}</button>
             }
 
@@ -325,6 +356,10 @@ impl Component for ChanceComponent {
                 onchange.emit(ChanceCallback::Delete(idx));
                 true
             }
+            Msg::Spread(idx, spread) => {
+                onchange.emit(ChanceCallback::Spread(idx, spread));
+                false
+            }
             Msg::DoNothing => false,
         }
     }
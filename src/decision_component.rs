@@ -0,0 +1,166 @@
+// decision_component.rs
+use crate::label_component::LabelCallback;
+use crate::LabelComponent;
+use crate::NumComponent;
+use bayes_core::{decide, Threshold};
+use yew::prelude::*;
+use yew::virtual_dom::AttrValue;
+
+#[derive(Properties, PartialEq)]
+pub struct DecisionProps {
+    pub actions: Vec<String>,
+    pub utilities: Vec<Vec<f64>>,
+    pub posterior: Vec<f64>,
+    pub hypotheses: Vec<AttrValue>,
+    pub color: Vec<usize>,
+    pub onchange: Callback<DecisionCallback>,
+}
+
+pub enum DecisionCallback {
+    AddAction,
+    RenameAction(usize, String),
+    DeleteAction(usize),
+    Utility(usize, usize, f64),
+}
+
+pub enum Msg {
+    AddAction,
+    RenameAction(usize, String),
+    DeleteAction(usize),
+    Utility(usize, usize, f64),
+}
+
+pub struct DecisionComponent {}
+
+fn threshold_text(threshold: &Threshold, props: &DecisionProps) -> Option<String> {
+    let hyp = &props.hypotheses[threshold.hypothesis];
+    let mut parts = Vec::new();
+    if let Some((p, action_idx)) = threshold.below {
+        parts.push(format!(
+            "falls below {:.1}% ({})",
+            p, props.actions[action_idx]
+        ));
+    }
+    if let Some((p, action_idx)) = threshold.above {
+        parts.push(format!(
+            "rises above {:.1}% ({})",
+            p, props.actions[action_idx]
+        ));
+    }
+    if parts.is_empty() {
+        None
+    } else {
+        Some(format!(
+            "Best action changes if P({}) {}",
+            hyp,
+            parts.join(" or ")
+        ))
+    }
+}
+
+impl Component for DecisionComponent {
+    type Message = Msg;
+    type Properties = DecisionProps;
+
+    fn create(_ctx: &yew::Context<Self>) -> Self {
+        Self {}
+    }
+
+    fn update(&mut self, ctx: &yew::Context<Self>, msg: Self::Message) -> bool {
+        let onchange = ctx.props().onchange.clone();
+        match msg {
+            Msg::AddAction => onchange.emit(DecisionCallback::AddAction),
+            Msg::RenameAction(action_idx, name) => {
+                onchange.emit(DecisionCallback::RenameAction(action_idx, name))
+            }
+            Msg::DeleteAction(action_idx) => {
+                onchange.emit(DecisionCallback::DeleteAction(action_idx))
+            }
+            Msg::Utility(action_idx, hyp_idx, value) => {
+                onchange.emit(DecisionCallback::Utility(action_idx, hyp_idx, value))
+            }
+        }
+        false
+    }
+
+    fn view(&self, ctx: &yew::Context<Self>) -> Html {
+        let props = ctx.props();
+        let decision = decide(&props.posterior, &props.utilities);
+        let onclick_add = ctx.link().callback(|_e: MouseEvent| Msg::AddAction);
+
+        let header = props.hypotheses.iter().enumerate().map(|(hyp_idx, hyp)| {
+            html! { <th class={format!("e{}", props.color[hyp_idx])}>{hyp.clone()}</th> }
+        });
+
+        let rows = props.actions.iter().enumerate().map(|(action_idx, action)| {
+            let onchange_name =
+                ctx.link()
+                    .callback(move |label_change: LabelCallback| match label_change {
+                        LabelCallback::Delete => Msg::DeleteAction(action_idx),
+                        LabelCallback::LabelEdit(name) => Msg::RenameAction(action_idx, name),
+                    });
+            let cells = props.utilities[action_idx].iter().enumerate().map(|(hyp_idx, utility)| {
+                let onchange_utility = ctx
+                    .link()
+                    .callback(move |value: f64| Msg::Utility(action_idx, hyp_idx, value));
+                html! {
+                    <td>
+                        <NumComponent min_value={None} max_value={None} force_value={Some(*utility)}
+                            class={AttrValue::from("utility")} placeholder={AttrValue::from("0")}
+                            onchange={onchange_utility}
+                        />
+                    </td>
+                }
+            });
+            let is_best = decision.as_ref().is_some_and(|d| d.best == action_idx);
+            let expected = decision
+                .as_ref()
+                .map(|d| format!("{:.2}", d.expected[action_idx]))
+                .unwrap_or_else(|| "–".to_string());
+            html! {
+                <tr class={if is_best { "best" } else { "" }}>
+                    <th>
+                        <LabelComponent
+                            class={AttrValue::from("action-label")}
+                            placeholder={AttrValue::from(action.clone())}
+                            onchange={onchange_name}
+                            deleteable={true}
+                        />
+                    </th>
+                    {for cells}
+                    <td class="expected-utility">{expected}</td>
+                </tr>
+            }
+        });
+
+        let summary = match &decision {
+            Some(decision) => {
+                let thresholds = decision
+                    .thresholds
+                    .iter()
+                    .filter_map(|threshold| threshold_text(threshold, props))
+                    .map(|text| html! { <li>{text}</li> });
+                html! {
+                    <>
+                        <div class="best-action">
+                            {format!("Best action: {}", props.actions[decision.best])}
+                        </div>
+                        <ul class="thresholds">{for thresholds}</ul>
+                    </>
+                }
+            }
+            None => html! {},
+        };
+
+        html! {
+            <div class="decision">
+                <table class="decision-table">
+                    <tr><th>{"Utility"}</th>{for header}<th>{"Expected"}</th></tr>
+                    {for rows}
+                </table>
+                <button class="add-action" onclick={onclick_add}>{"+ action"}</button>
+                {summary}
+            </div>
+        }
+    }
+}
@@ -0,0 +1,79 @@
+// diagnostics_component.rs
+use bayes_core::{Diagnostic, Severity};
+use yew::prelude::*;
+
+#[derive(Properties, PartialEq)]
+pub struct DiagnosticsProps {
+    pub diagnostics: Vec<Diagnostic>,
+    pub on_close: Callback<()>,
+}
+
+pub enum Msg {
+    Close,
+}
+
+/// Everything wrong with a file that couldn't be loaded, or the warnings
+/// about one that did, with where each problem is and how to fix it.
+pub struct DiagnosticsComponent {}
+
+impl Component for DiagnosticsComponent {
+    type Message = Msg;
+    type Properties = DiagnosticsProps;
+
+    fn create(_ctx: &yew::Context<Self>) -> Self {
+        Self {}
+    }
+
+    fn update(&mut self, ctx: &yew::Context<Self>, msg: Self::Message) -> bool {
+        match msg {
+            Msg::Close => ctx.props().on_close.emit(()),
+        }
+        false
+    }
+
+    fn view(&self, ctx: &yew::Context<Self>) -> Html {
+        let diagnostics = &ctx.props().diagnostics;
+        let items = diagnostics.iter().map(|diagnostic| {
+            let place = match diagnostic.line {
+                0 => "Whole file".to_string(),
+                line => format!("Line {}, column {}", line, diagnostic.column),
+            };
+            let class = match diagnostic.severity {
+                Severity::Error => "diagnostic",
+                Severity::Warning => "diagnostic warning",
+            };
+            html! {
+                <li class={class}>
+                    <span class="diagnostic-place">{place}</span>
+                    <span class="diagnostic-message">{&diagnostic.message}</span>
+                    {if diagnostic.text.is_empty() {
+                        html! {}
+                    } else {
+                        html! { <code class="diagnostic-text">{&diagnostic.text}</code> }
+                    }}
+                    <div class="diagnostic-fix">{&diagnostic.fix}</div>
+                </li>
+            }
+        });
+        let loaded = diagnostics
+            .iter()
+            .all(|diagnostic| diagnostic.severity == Severity::Warning);
+        let title = match (loaded, diagnostics.len()) {
+            (true, 1) => "Loaded the file, with 1 warning".to_string(),
+            (true, count) => format!("Loaded the file, with {} warnings", count),
+            (false, 1) => "Couldn't load the file: 1 problem".to_string(),
+            (false, count) => format!("Couldn't load the file: {} problems", count),
+        };
+        let onclick = ctx.link().callback(|_e: MouseEvent| Msg::Close);
+
+        html! {
+            <div class={if loaded { "diagnostics loaded" } else { "diagnostics" }}>
+                <div class="diagnostics-header">
+                    <b>{title}</b>
+                    <button class="diagnostics-close" title="Dismiss" onclick={onclick}>{"×"}</button>
+                </div>
+                <ul>{for items}</ul>
+            </div>
+        }
+    }
+}
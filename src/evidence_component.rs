@@ -1,21 +1,58 @@
 // evidence_component.rs
-use crate::chance_component::percentize;
 use crate::label_component::LabelCallback;
+use crate::layout::column_width;
 use crate::LabelComponent;
 // use crate::SliderComponent;
+use crate::spread_component::SpreadComponent;
 use crate::NumComponent;
-use web_sys::HtmlInputElement;
+use bayes_core::{
+    db_to_ratio, likelihood_ratios, log_odds_in_db, normalize, pairwise_log_odds_in_db,
+    pairwise_log_odds_in_db_from_logs, percentize, ratio_to_db, strongest, Categorical, Continuous,
+    Distribution, EntryMode, Influence, Spread, StrengthUnit, ValueOfInformation,
+};
+use web_sys::{HtmlInputElement, HtmlSelectElement};
 
 use yew::prelude::*;
 use yew::virtual_dom::AttrValue;
 
 #[derive(Properties, PartialEq)]
 pub struct EvidenceProps {
+    /// Position of this row in the model, which may differ from where it's shown.
+    pub index: usize,
     pub hypotheses: Vec<AttrValue>,
     pub label: String,
     pub onchange: Callback<EvidenceCallback>,
     pub prior_odds: Vec<f64>,
     pub likelihoods: Vec<f64>,
+    /// Natural-log weights of every row up to and including this one, as
+    /// `log_recalculate_to` gives them.
+    pub cumulative_log_odds: Vec<f64>,
+    #[prop_or(true)]
+    pub active: bool,
+    /// Planned evidence hasn't been observed yet, so it never moves the posterior.
+    #[prop_or(false)]
+    pub planned: bool,
+    /// What observing this row is expected to tell us, for planned rows.
+    #[prop_or(None)]
+    pub information: Option<ValueOfInformation>,
+    /// Uncertainty inputs for each likelihood, shown only when given.
+    #[prop_or(None)]
+    pub spreads: Option<Vec<Option<Spread>>>,
+    #[prop_or(None)]
+    pub influence: Option<Influence>,
+    /// The outcome table, if this is categorical rather than yes/no evidence.
+    #[prop_or(None)]
+    pub categorical: Option<Categorical>,
+    /// The likelihood functions, if this evidence is a measured value.
+    #[prop_or(None)]
+    pub continuous: Option<Continuous>,
+    /// How yes/no likelihoods are typed in: percentages, or ratios against
+    /// a reference hypothesis.
+    #[prop_or(EntryMode::Percent)]
+    pub entry_mode: EntryMode,
+    /// The unit evidence strength is shown in.
+    #[prop_or(StrengthUnit::Decibels)]
+    pub unit: StrengthUnit,
     #[prop_or(false)]
     pub last: bool,
     pub color: Vec<usize>,
@@ -25,12 +62,27 @@ pub struct EvidenceComponent {
     pub evidence: AttrValue,
     pub likelihoods: Vec<f64>,
     pub bayes_factors: Vec<f64>,
+    pub show_matrix: bool,
 }
 
 pub enum Msg {
     EditLabel(AttrValue),
     Likelihood(usize, f64),
     Delete,
+    ToggleMatrix,
+    ToggleActive,
+    TogglePlanned,
+    Spread(usize, Option<Spread>),
+    SetKind(KindChoice),
+    Observe(usize),
+    OutcomeLikelihood(usize, usize, f64),
+    AddOutcome,
+    RenameOutcome(usize, String),
+    DeleteOutcome(usize),
+    Observation(f64),
+    Distribution(usize, Distribution),
+    EntryMode(EntryMode),
+    Ratio(usize, f64),
     DoNothing,
 }
 
@@ -38,18 +90,156 @@ pub enum EvidenceCallback {
     OddsUpdate(usize, f64),
     LabelEdit(String),
     Delete,
+    ToggleActive,
+    TogglePlanned,
+    Spread(usize, Option<Spread>),
+    Kind(KindChoice),
+    Observe(usize),
+    OutcomeLikelihood(usize, usize, f64),
+    AddOutcome,
+    RenameOutcome(usize, String),
+    DeleteOutcome(usize),
+    Observation(f64),
+    Distribution(usize, Distribution),
+    EntryMode(EntryMode),
+    Ratio(usize, f64),
+}
+
+/// The evidence kinds offered in the picker.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum KindChoice {
+    Binary,
+    Categorical,
+    Continuous,
 }
 
-pub fn normalize(odds: Vec<f64>) -> Vec<f64> {
-    let total = odds.iter().sum::<f64>();
-    odds.iter().map(|x| x / total).collect()
+/// Forecasts are listed outcome by outcome only up to this many outcomes.
+const MAX_FORECASTS: usize = 10;
+
+fn information_panel(
+    information: &ValueOfInformation,
+    hypotheses: &[AttrValue],
+    color: &[usize],
+) -> Html {
+    let forecasts = information.forecasts.iter().map(|forecast| {
+        let posterior = forecast.posterior.iter().enumerate().map(|(hyp_idx, p)| {
+            html! {
+                <span class={format!("e{}", color[hyp_idx])} title={hypotheses[hyp_idx].clone()}>
+                    {format!(" {:.1}%", p)}
+                </span>
+            }
+        });
+        html! {
+            <tr>
+                <td>{forecast.outcome.clone()}</td>
+                <td>{format!("{:.1}%", 100.0 * forecast.probability)}</td>
+                <td>{"→"}{for posterior}</td>
+            </tr>
+        }
+    });
+    html! {
+        <div class="information-panel">
+            <div class="information-summary">
+                {format!(
+                    "Expected information {:.2} bits · {:.1}% chance the leading hypothesis changes",
+                    information.entropy_reduction,
+                    100.0 * information.leader_change
+                )}
+            </div>
+            if information.forecasts.len() <= MAX_FORECASTS {
+                <table class="forecasts">
+                    <tr><th>{"If observed"}</th><th>{"Chance"}</th><th>{"Posterior"}</th></tr>
+                    {for forecasts}
+                </table>
+            }
+        </div>
+    }
 }
 
-pub fn log_odds_in_db(odds: Vec<f64>) -> Vec<f64> {
-    normalize(odds)
+/// Points along each density curve.
+const PLOT_POINTS: usize = 200;
+const PLOT_HEIGHT: f64 = 100.0;
+
+/// Every hypothesis's density over a shared x axis, with the observation marked.
+fn density_plot(continuous: &Continuous, color: &[usize], width: f64) -> Html {
+    let (low, high) = continuous.range();
+    let xs: Vec<f64> = (0..=PLOT_POINTS)
+        .map(|idx| low + (high - low) * idx as f64 / PLOT_POINTS as f64)
+        .collect();
+    let curves: Vec<Vec<f64>> = continuous
+        .distributions
+        .iter()
+        .map(|dist| xs.iter().map(|x| dist.density(*x)).collect())
+        .collect();
+    let max = curves
         .iter()
-        .map(|x| 10.0 * (x / (1.0 - x)).log(10.0))
-        .collect()
+        .flatten()
+        .cloned()
+        .filter(|d| d.is_finite())
+        .fold(0.0, f64::max)
+        .max(1e-12);
+    let to_x = |x: f64| width * (x - low) / (high - low);
+    let to_y = |d: f64| PLOT_HEIGHT * (1.0 - (d / max).min(1.0));
+
+    let paths = curves.iter().enumerate().map(|(hyp_idx, curve)| {
+        let points: Vec<String> = xs
+            .iter()
+            .zip(curve)
+            .map(|(x, d)| format!("{:.1},{:.1}", to_x(*x), to_y(*d)))
+            .collect();
+        html! {
+            <path class={format!("density-curve e{}", color[hyp_idx])} d={format!("M{}", points.join(" L"))} />
+        }
+    });
+    let observed_x = to_x(continuous.observed);
+    html! {
+        <svg class="density-plot" width={width.to_string()} height={(PLOT_HEIGHT + 16.0).to_string()}>
+            {for paths}
+            <line class="density-observed" x1={observed_x.to_string()} x2={observed_x.to_string()}
+                y1="0" y2={PLOT_HEIGHT.to_string()} />
+            <text class="density-axis" x="0" y={(PLOT_HEIGHT + 12.0).to_string()}>{format!("{:.3}", low)}</text>
+            <text class="density-axis" x={width.to_string()} y={(PLOT_HEIGHT + 12.0).to_string()} text-anchor="end">
+                {format!("{:.3}", high)}
+            </text>
+            <text class="density-axis" x={observed_x.to_string()} y={(PLOT_HEIGHT + 12.0).to_string()} text-anchor="middle">
+                {format!("observed {}", continuous.observed)}
+            </text>
+        </svg>
+    }
+}
+
+fn matrix_table(
+    title: String,
+    hypotheses: &[AttrValue],
+    color: &[usize],
+    matrix: Vec<Vec<f64>>,
+    unit: StrengthUnit,
+) -> Html {
+    let header = hypotheses.iter().enumerate().map(|(idx, hyp)| {
+        html! { <th class={format!("e{}", color[idx])}>{hyp.clone()}</th> }
+    });
+    let rows = hypotheses.iter().enumerate().map(|(row, hyp)| {
+        let cells = matrix[row].iter().enumerate().map(|(col, db)| {
+            if row == col {
+                html! { <td class="eblank">{"·"}</td> }
+            } else {
+                html! { <td>{unit.format_value(*db)}</td> }
+            }
+        });
+        html! {
+            <tr>
+                <th class={format!("e{}", color[row])}>{hyp.clone()}</th>
+                {for cells}
+            </tr>
+        }
+    });
+    html! {
+        <table class="bayes-matrix">
+            <caption>{title}</caption>
+            <tr><th>{unit.name()}</th>{for header}</tr>
+            {for rows}
+        </table>
+    }
 }
 
 impl Component for EvidenceComponent {
@@ -61,6 +251,7 @@ impl Component for EvidenceComponent {
             evidence: AttrValue::from(ctx.props().label.clone()),
             bayes_factors: log_odds_in_db(normalize(ctx.props().likelihoods.clone())),
             likelihoods: ctx.props().likelihoods.clone(),
+            show_matrix: false,
         }
     }
 
@@ -101,33 +292,262 @@ impl Component for EvidenceComponent {
             })
         };
 
+        let onchange_spread = move |hyp_idx: usize| {
+            ctx.link()
+                .callback(move |spread: Option<Spread>| Msg::Spread(hyp_idx, spread))
+        };
+
         let prior_odds_percent = percentize(ctx.props().prior_odds.clone());
+        // Inactive and planned evidence passes every hypothesis through unchanged.
+        let counts = ctx.props().active && !ctx.props().planned;
+        let after_likelihoods = if counts {
+            self.likelihoods.clone()
+        } else {
+            vec![1.0; self.likelihoods.len()]
+        };
 
         let display_after_bar = ctx.props().hypotheses.iter().enumerate().map(move |odds|
             html!{
                 <>
-                    <div class={format!("b{} d{} c0", ctx.props().color[odds.0], odds.0)} style={format!("width:{}%", prior_odds_percent[odds.0]*self.likelihoods[odds.0])}>
+                    <div class={format!("b{}", ctx.props().color[odds.0])} style={format!("width:{}%", prior_odds_percent[odds.0]*after_likelihoods[odds.0])}>
 
                     </div>
-                    <div class={format!("a{} d{} c1", ctx.props().color[odds.0], odds.0)} style={format!("width:{}%", prior_odds_percent[odds.0]*(1.0-self.likelihoods[odds.0]))}>
+                    <div class={format!("a{}", ctx.props().color[odds.0])} style={format!("width:{}%", prior_odds_percent[odds.0]*(1.0-after_likelihoods[odds.0]))}>
 
                     </div>
                 </>
                 });
 
-        let display_hypothesis_evidence = ctx.props().hypotheses.iter().enumerate().map(move |hypotheses|
-            html!{
-            <div class={format!("d{}", hypotheses.0)}>
+        let onchange_outcome = move |hyp_idx: usize, outcome_idx: usize| {
+            ctx.link().callback(move |new_odds: f64| {
+                Msg::OutcomeLikelihood(hyp_idx, outcome_idx, new_odds * 0.01)
+            })
+        };
+
+        let display_categorical = move |hyp_idx: usize, categorical: &Categorical| {
+            let color = ctx.props().color[hyp_idx];
+            let row = &categorical.table[hyp_idx];
+            let inputs = categorical.outcomes.iter().enumerate().map(|(outcome_idx, outcome)| {
+                let class = if outcome_idx == categorical.observed { "like observed" } else { "like" };
+                html! {
+                    <div class="evidence-center outcome-input" title={outcome.clone()}>
+                        <NumComponent min_value={0.0} max_value={100.0}
+                            force_value={Some(row[outcome_idx] * 100.0)} class={AttrValue::from(class)}
+                            placeholder={AttrValue::from("0")} onchange={onchange_outcome(hyp_idx, outcome_idx)} percent={true}
+                        />
+                        <div class="percent-symbol">
+                            <button class="no_button">{"%"}</button>
+                        </div>
+                    </div>
+                }
+            });
+            // One segment per outcome, with the observed one in full color.
+            let segments = categorical.outcomes.iter().enumerate().map(|(outcome_idx, outcome)| {
+                let class = if outcome_idx == categorical.observed {
+                    format!("b{} outcome-seg", color)
+                } else {
+                    format!("a{} outcome-seg", color)
+                };
+                html! {
+                    <div class={class} title={format!("{}: {:.1}%", outcome, 100.0 * row[outcome_idx])}
+                        style={format!("width:{}%", 100.0 * row[outcome_idx])}>
+                    </div>
+                }
+            });
+            html! {
+                <div class="hyp-evidence categorical">
+                    {for inputs}
+                    <div class="before-bar outcomes">
+                        {for segments}
+                    </div>
+                </div>
+            }
+        };
+
+        let onchange_distribution = move |hyp_idx: usize, dist: Distribution| {
+            ctx.link().callback(move |e: Event| {
+                let select: HtmlSelectElement = e.target_unchecked_into();
+                match Distribution::with_name(&select.value()) {
+                    // Keep the parameters both distributions share, e.g. normal to log-normal.
+                    Ok(new_dist) if new_dist.name() != dist.name() => {
+                        let params = dist.params();
+                        let mut new_dist = new_dist;
+                        for (idx, value) in params.iter().enumerate() {
+                            if idx < new_dist.params().len()
+                                && new_dist.with_param(idx, *value).validate().is_ok()
+                            {
+                                new_dist = new_dist.with_param(idx, *value);
+                            }
+                        }
+                        Msg::Distribution(hyp_idx, new_dist)
+                    }
+                    _ => Msg::DoNothing,
+                }
+            })
+        };
+
+        let onchange_param = move |hyp_idx: usize, dist: Distribution, param_idx: usize| {
+            ctx.link().callback(move |value: f64| {
+                Msg::Distribution(hyp_idx, dist.with_param(param_idx, value))
+            })
+        };
+
+        let display_continuous = move |hyp_idx: usize, continuous: &Continuous| {
+            let dist = continuous.distributions[hyp_idx];
+            let options = Distribution::NAMES.iter().map(|name| {
+                html! { <option value={*name} selected={*name == dist.name()}>{*name}</option> }
+            });
+            let params = dist.param_names().iter().zip(dist.params()).enumerate().map(|(param_idx, (name, value))| {
+                html! {
+                    <label class="dist-param">
+                        {*name}
+                        <NumComponent min_value={None} max_value={None} force_value={Some(value)}
+                            class={AttrValue::from("param")} placeholder={AttrValue::from("0")}
+                            onchange={onchange_param(hyp_idx, dist, param_idx)}
+                        />
+                    </label>
+                }
+            });
+            let likelihood = self.likelihoods.get(hyp_idx).copied().unwrap_or(0.0);
+            html! {
+                <div class="hyp-evidence continuous">
+                    <select class="dist-picker" onchange={onchange_distribution(hyp_idx, dist)}>{for options}</select>
+                    {for params}
+                    if let Err(e) = dist.validate() {
+                        <div class="invalid">{e}</div>
+                    } else {
+                        <div class="dist-density" title="Density at the observed value">
+                            {format!("density {:.4}", dist.density(continuous.observed))}
+                        </div>
+                    }
+                    // A density isn't a probability, so there's no "not observed"
+                    // part; the bar is relative to the most likely hypothesis.
+                    <div class="before-bar" title="Density at the observed value, relative to the highest">
+                        <div class={format!("b{}", ctx.props().color[hyp_idx])} style={format!("width:{}%", 100.0 * likelihood)}>
+                        </div>
+                    </div>
+                </div>
+            }
+        };
+
+        let entry_mode = ctx.props().entry_mode;
+        let ratios = entry_mode
+            .reference()
+            .map(|reference| likelihood_ratios(&self.likelihoods, reference));
+        let onchange_ratio = move |hyp_idx: usize| {
+            ctx.link().callback(move |value: f64| {
+                let ratio = match entry_mode {
+                    EntryMode::Decibels { .. } => db_to_ratio(value),
+                    _ => value,
+                };
+                Msg::Ratio(hyp_idx, ratio)
+            })
+        };
+
+        let entry_value = |mode: EntryMode, ratio: f64| match mode {
+            EntryMode::Decibels { .. } => ratio_to_db(ratio),
+            _ => ratio,
+        };
+
+        // The input for one likelihood in the row's entry mode, with the other
+        // representation shown underneath. The reference hypothesis is always
+        // entered as a percentage, since every ratio is relative to it.
+        let display_entry = move |hyp_idx: usize| {
+            let likelihood = self.likelihoods[hyp_idx];
+            let percent_input = html! {
                 <div class="evidence-center">
                     <NumComponent min_value={0.0} max_value={100.0}
-                    force_value={Some(self.likelihoods[hypotheses.0]*100.0)} class={AttrValue::from("like")}
-                    placeholder={AttrValue::from("50")} onchange={&onchange_odds(hypotheses.0)}
+                    force_value={Some(likelihood*100.0)} class={AttrValue::from("like")}
+                    placeholder={AttrValue::from("50")} onchange={&onchange_odds(hyp_idx)} percent={true}
                     />
                     <div class="percent-symbol">
                        <button class="no_button" >{"%"}</button>
                     </div>
                 </div>
+            };
+            match (entry_mode, &ratios) {
+                (EntryMode::Percent, _) | (_, None) => percent_input,
+                (EntryMode::Ratio { reference } | EntryMode::Decibels { reference }, _)
+                    if reference == hyp_idx =>
+                {
+                    html! {
+                        <>
+                            {percent_input}
+                            <div class="likelihood-alt">{"reference"}</div>
+                        </>
+                    }
+                }
+                // Against a 0% reference, or in decibels for a 0% likelihood,
+                // there's no finite value to type in.
+                (mode, Some(ratios)) if !entry_value(mode, ratios[hyp_idx]).is_finite() => {
+                    html! {
+                        <>
+                            {percent_input}
+                            <div class="likelihood-alt" title="The ratio to the reference is infinite or undefined here, so this is entered as a percentage">{"—"}</div>
+                        </>
+                    }
+                }
+                (mode, Some(ratios)) => {
+                    let ratio = ratios[hyp_idx];
+                    let value = entry_value(mode, ratio);
+                    // The other representation is in the preferred unit, unless
+                    // that's the one being typed in.
+                    let strength_unit = ctx.props().unit;
+                    let (min_value, unit, alt_unit) = match mode {
+                        EntryMode::Decibels { .. } => (
+                            None,
+                            "dB",
+                            if strength_unit == StrengthUnit::Decibels {
+                                StrengthUnit::Ratio
+                            } else {
+                                strength_unit
+                            },
+                        ),
+                        _ => (
+                            Some(0.0),
+                            "×",
+                            if strength_unit == StrengthUnit::Ratio {
+                                StrengthUnit::Decibels
+                            } else {
+                                strength_unit
+                            },
+                        ),
+                    };
+                    let alt = alt_unit.format(ratio_to_db(ratio));
+                    html! {
+                        <>
+                            <div class="evidence-center">
+                                <NumComponent min_value={min_value} max_value={None}
+                                force_value={Some(value)} class={AttrValue::from("like ratio")}
+                                placeholder={AttrValue::from(if unit == "dB" { "0" } else { "1" })} onchange={onchange_ratio(hyp_idx)}
+                                />
+                                <div class="percent-symbol">
+                                   <button class="no_button" >{unit}</button>
+                                </div>
+                            </div>
+                            <div class="likelihood-alt">{format!("{} · {:.1}%", alt, 100.0 * likelihood)}</div>
+                        </>
+                    }
+                }
+            }
+        };
+
+        let display_hypothesis_evidence = ctx.props().hypotheses.iter().enumerate().map(move |hypotheses|
+            if let Some(categorical) = &ctx.props().categorical {
+                display_categorical(hypotheses.0, categorical)
+            } else if let Some(continuous) = &ctx.props().continuous {
+                display_continuous(hypotheses.0, continuous)
+            } else {
+            html!{
+            <div class="hyp-evidence">
+                {display_entry(hypotheses.0)}
                 <input type="range" min=0.0 max=1.0 step={0.001} value={AttrValue::from((self.likelihoods[hypotheses.0]).to_string())} class="slider" ontouchmove={ontouchmove(hypotheses.0)} oninput={onslide(hypotheses.0)} />
+                if let Some(spreads) = &ctx.props().spreads {
+                    <div class="evidence-spread">
+                        <SpreadComponent spread={spreads.get(hypotheses.0).copied().flatten()} scale={0.01} percent={true}
+                            onchange={onchange_spread(hypotheses.0)}/>
+                    </div>
+                }
                 <div class="before-bar">
                     <div class={format!("b{} c0",ctx.props().color[hypotheses.0])} style={format!("width:{}%", 100.0*self.likelihoods[hypotheses.0])}>
                     </div>
@@ -137,22 +557,15 @@ impl Component for EvidenceComponent {
                     </div>
                 </div>
             </div>
+            }
          });
 
-        let (max_index, _) = self.bayes_factors.iter().enumerate().fold(
-            (Some(0), Some(f64::NEG_INFINITY)),
-            |(max_idx, max_val), (idx, &val)| match val.partial_cmp(&max_val.unwrap()) {
-                Some(std::cmp::Ordering::Greater) => (Some(idx), Some(val)),
-                Some(std::cmp::Ordering::Equal) => (None, Some(val)),
-                _ => (max_idx, max_val),
-            },
-        );
-
-        let display_log_odds = if let Some(max_idx) = max_index {
+        let unit = ctx.props().unit;
+        let display_log_odds = if let Some(max_idx) = strongest(&self.bayes_factors) {
             html! {
                 <div class={format!("e{idx}", idx=ctx.props().color[max_idx])}>
                 <b>
-                {format_num::format_num!("+.1f", self.bayes_factors[max_idx])}{" db"}
+                {unit.format(self.bayes_factors[max_idx])}
                 </b>
                 </div>
             }
@@ -160,14 +573,171 @@ impl Component for EvidenceComponent {
             html! {
                 <div class="eblank">
                 <b>
-                {"0 db"}
+                {unit.format(0.0)}
                 </b>
                 </div>
             }
         };
-        let col_str = "200px ".repeat(10);
+        let cols = ctx.props().hypotheses.len();
+        let col_width = column_width(cols);
+        let col_str = format!("repeat({}, {}px)", cols, col_width);
+
+        let onclick_matrix = ctx.link().callback(|_e: MouseEvent| Msg::ToggleMatrix);
+        let onclick_active = ctx.link().callback(|_e: MouseEvent| Msg::ToggleActive);
+        let onclick_planned = ctx.link().callback(|_e: MouseEvent| Msg::TogglePlanned);
+        let item_class = format!(
+            "evidence-item{}{}",
+            if ctx.props().active { "" } else { " inactive" },
+            if ctx.props().planned { " planned" } else { "" }
+        );
+        let onchange_kind = ctx.link().callback(|e: Event| {
+            let select: HtmlSelectElement = e.target_unchecked_into();
+            match select.value().as_str() {
+                "categorical" => Msg::SetKind(KindChoice::Categorical),
+                "continuous" => Msg::SetKind(KindChoice::Continuous),
+                _ => Msg::SetKind(KindChoice::Binary),
+            }
+        });
+        let kind = if ctx.props().categorical.is_some() {
+            KindChoice::Categorical
+        } else if ctx.props().continuous.is_some() {
+            KindChoice::Continuous
+        } else {
+            KindChoice::Binary
+        };
+        let onchange_observation = ctx.link().callback(Msg::Observation);
+        let display_observation = match &ctx.props().continuous {
+            Some(continuous) => html! {
+                <label class="observation">
+                    {"Observed value "}
+                    <NumComponent min_value={None} max_value={None} force_value={Some(continuous.observed)}
+                        class={AttrValue::from("param")} placeholder={AttrValue::from("0")}
+                        onchange={onchange_observation}
+                    />
+                </label>
+            },
+            None => html! {},
+        };
+        let onchange_entry = ctx.link().callback(move |e: Event| {
+            let select: HtmlSelectElement = e.target_unchecked_into();
+            let reference = entry_mode.reference().unwrap_or(0);
+            match select.value().as_str() {
+                "ratio" => Msg::EntryMode(EntryMode::Ratio { reference }),
+                "decibels" => Msg::EntryMode(EntryMode::Decibels { reference }),
+                _ => Msg::EntryMode(EntryMode::Percent),
+            }
+        });
+        let onchange_reference = ctx.link().callback(move |e: Event| {
+            let select: HtmlSelectElement = e.target_unchecked_into();
+            match select.value().parse::<usize>() {
+                Ok(reference) => Msg::EntryMode(entry_mode.with_reference(reference)),
+                Err(_) => Msg::DoNothing,
+            }
+        });
+        let display_entry_mode = if kind == KindChoice::Binary {
+            let references = ctx.props().hypotheses.iter().enumerate().map(|(hyp_idx, hyp)| {
+                html! {
+                    <option value={hyp_idx.to_string()} selected={entry_mode.reference() == Some(hyp_idx)}>
+                        {hyp.clone()}
+                    </option>
+                }
+            });
+            html! {
+                <div class="entry-mode">
+                    <select class="entry-picker" onchange={onchange_entry} title="How likelihoods are entered">
+                        <option value="percent" selected={entry_mode == EntryMode::Percent}>{"Percent"}</option>
+                        <option value="ratio" selected={matches!(entry_mode, EntryMode::Ratio { .. })}>{"Ratio to"}</option>
+                        <option value="decibels" selected={matches!(entry_mode, EntryMode::Decibels { .. })}>{"dB vs"}</option>
+                    </select>
+                    if entry_mode != EntryMode::Percent {
+                        <select class="reference-picker" onchange={onchange_reference} title="Reference hypothesis">
+                            {for references}
+                        </select>
+                    }
+                </div>
+            }
+        } else {
+            html! {}
+        };
+        let onclick_add_outcome = ctx.link().callback(|_e: MouseEvent| Msg::AddOutcome);
+
+        let display_outcomes = match &ctx.props().categorical {
+            Some(categorical) => {
+                let deleteable = categorical.outcomes.len() > 2;
+                let outcomes = categorical.outcomes.iter().enumerate().map(|(outcome_idx, outcome)| {
+                    let onclick_observe = ctx.link().callback(move |_e: MouseEvent| Msg::Observe(outcome_idx));
+                    let onchange_name = ctx.link().callback(move |label_change: LabelCallback| match label_change {
+                        LabelCallback::Delete => Msg::DeleteOutcome(outcome_idx),
+                        LabelCallback::LabelEdit(label) => Msg::RenameOutcome(outcome_idx, label),
+                    });
+                    let observed = outcome_idx == categorical.observed;
+                    html! {
+                        <div class={if observed { "outcome observed" } else { "outcome" }}>
+                            <button class="observe-toggle" onclick={onclick_observe}
+                                title={if observed { "Observed outcome" } else { "Mark as the observed outcome" }}>
+                                {if observed { "◉" } else { "○" }}
+                            </button>
+                            <LabelComponent
+                                class={AttrValue::from("outcome-label")}
+                                placeholder={AttrValue::from(outcome.clone())}
+                                onchange={onchange_name}
+                                deleteable={deleteable}
+                            />
+                        </div>
+                    }
+                });
+                html! {
+                    <div class="outcomes-list">
+                        {for outcomes}
+                        <button class="add-outcome" onclick={onclick_add_outcome}>{"+ outcome"}</button>
+                    </div>
+                }
+            }
+            None => html! {},
+        };
+
+        let display_influence = match &ctx.props().influence {
+            Some(influence) if counts => {
+                let without = ctx
+                    .props()
+                    .hypotheses
+                    .iter()
+                    .zip(&influence.posterior_without)
+                    .map(|(hyp, p)| format!("{}: {:.1}%", hyp, p))
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                html! {
+                    <div class="influence" title={format!("Posterior without this evidence: {}", without)}>
+                        {format!("moves posterior {:.1} pts", influence.shift)}
+                    </div>
+                }
+            }
+            _ => html! {},
+        };
+        let display_matrix = if self.show_matrix {
+            html! {
+                <div class="matrix-panel">
+                {matrix_table(
+                    format!("{} (row vs column)", self.evidence),
+                    &ctx.props().hypotheses,
+                    &ctx.props().color,
+                    pairwise_log_odds_in_db(&self.likelihoods),
+                    unit,
+                )}
+                {matrix_table(
+                    "All evidence so far".to_string(),
+                    &ctx.props().hypotheses,
+                    &ctx.props().color,
+                    pairwise_log_odds_in_db_from_logs(&ctx.props().cumulative_log_odds),
+                    unit,
+                )}
+                </div>
+            }
+        } else {
+            html! {}
+        };
         html! {
-            <div class="evidence-item">
+            <div class={item_class} id={format!("evidence-{}", ctx.props().index)}>
             <div class = "left">
                 <div class = "ev">
                 <LabelComponent
@@ -179,6 +749,26 @@ impl Component for EvidenceComponent {
                 <div class="log-odds mobile">
                 {display_log_odds.clone()}
                 </div>
+                {display_influence}
+                <button class="active-toggle" onclick={onclick_active}
+                    title={if ctx.props().active { "Exclude from the posterior" } else { "Include in the posterior" }}>
+                    {if ctx.props().active { "● active" } else { "○ inactive" }}
+                </button>
+                <button class="planned-toggle" onclick={onclick_planned}
+                    title={if ctx.props().planned { "Mark as observed" } else { "Mark as planned, to see what observing it would tell us" }}>
+                    {if ctx.props().planned { "◇ planned" } else { "◆ observed" }}
+                </button>
+                <button class="matrix-toggle" onclick={onclick_matrix}>
+                    {format!("{} {} matrix", if self.show_matrix { "▾" } else { "▸" }, unit.name())}
+                </button>
+                <select class="kind-picker" onchange={onchange_kind} title="What kind of observation this is">
+                    <option value="binary" selected={kind == KindChoice::Binary}>{"Yes / no"}</option>
+                    <option value="categorical" selected={kind == KindChoice::Categorical}>{"Several outcomes"}</option>
+                    <option value="continuous" selected={kind == KindChoice::Continuous}>{"Measured value"}</option>
+                </select>
+                {display_entry_mode}
+                {display_outcomes}
+                {display_observation}
 
                 </div>
             </div>
@@ -195,11 +785,23 @@ impl Component for EvidenceComponent {
             {display_log_odds}
             </div>
 
-            <div class="after-bar" style={format!("width:{}px",200*ctx.props().hypotheses.len())}>
-            <div class="bart">
+            <div class="after-bar" style={format!("width:{}px",col_width*cols)}>
+            <div class="bart" data-ev={ctx.props().index.to_string()}>
             {for display_after_bar}
             </div>
             </div>
+
+            if let Some(information) = &ctx.props().information {
+                {information_panel(information, &ctx.props().hypotheses, &ctx.props().color)}
+            }
+
+            if let Some(continuous) = &ctx.props().continuous {
+                <div class="density-panel">
+                    {density_plot(continuous, &ctx.props().color, (col_width * cols) as f64)}
+                </div>
+            }
+
+            {display_matrix}
             </div>
         }
     }
@@ -247,6 +849,78 @@ impl Component for EvidenceComponent {
                 ctx.props().onchange.emit(EvidenceCallback::Delete);
                 true
             }
+            Msg::ToggleMatrix => {
+                self.show_matrix = !self.show_matrix;
+                true
+            }
+            Msg::ToggleActive => {
+                ctx.props().onchange.emit(EvidenceCallback::ToggleActive);
+                true
+            }
+            Msg::Spread(hyp_idx, spread) => {
+                ctx.props()
+                    .onchange
+                    .emit(EvidenceCallback::Spread(hyp_idx, spread));
+                false
+            }
+            Msg::TogglePlanned => {
+                ctx.props().onchange.emit(EvidenceCallback::TogglePlanned);
+                true
+            }
+            Msg::SetKind(kind) => {
+                ctx.props().onchange.emit(EvidenceCallback::Kind(kind));
+                false
+            }
+            Msg::Observation(value) => {
+                ctx.props()
+                    .onchange
+                    .emit(EvidenceCallback::Observation(value));
+                false
+            }
+            Msg::Distribution(hyp_idx, dist) => {
+                ctx.props()
+                    .onchange
+                    .emit(EvidenceCallback::Distribution(hyp_idx, dist));
+                false
+            }
+            Msg::EntryMode(mode) => {
+                ctx.props().onchange.emit(EvidenceCallback::EntryMode(mode));
+                false
+            }
+            Msg::Ratio(hyp_idx, ratio) => {
+                ctx.props()
+                    .onchange
+                    .emit(EvidenceCallback::Ratio(hyp_idx, ratio));
+                false
+            }
+            Msg::Observe(outcome_idx) => {
+                ctx.props()
+                    .onchange
+                    .emit(EvidenceCallback::Observe(outcome_idx));
+                false
+            }
+            Msg::OutcomeLikelihood(hyp_idx, outcome_idx, p) => {
+                ctx.props()
+                    .onchange
+                    .emit(EvidenceCallback::OutcomeLikelihood(hyp_idx, outcome_idx, p));
+                false
+            }
+            Msg::AddOutcome => {
+                ctx.props().onchange.emit(EvidenceCallback::AddOutcome);
+                false
+            }
+            Msg::RenameOutcome(outcome_idx, name) => {
+                ctx.props()
+                    .onchange
+                    .emit(EvidenceCallback::RenameOutcome(outcome_idx, name));
+                false
+            }
+            Msg::DeleteOutcome(outcome_idx) => {
+                ctx.props()
+                    .onchange
+                    .emit(EvidenceCallback::DeleteOutcome(outcome_idx));
+                false
+            }
 
             Msg::DoNothing => false,
         }
@@ -0,0 +1,192 @@
+// frequency_component.rs
+use bayes_core::FrequencyStep;
+use serde::{Deserialize, Serialize};
+use yew::prelude::*;
+use yew::virtual_dom::AttrValue;
+
+/// People shown in the icon array and the tree.
+pub const POPULATION: usize = 1000;
+const ICON_COLUMNS: usize = 40;
+const ICON_SIZE: f64 = 10.0;
+
+/// Which natural-frequency view is shown under the posterior, if any.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FrequencyView {
+    #[default]
+    Hidden,
+    Icons,
+    Tree,
+}
+
+#[derive(Properties, PartialEq)]
+pub struct FrequencyProps {
+    pub view: FrequencyView,
+    /// The prior, then one step per evidence row that counts.
+    pub steps: Vec<FrequencyStep>,
+    pub hypotheses: Vec<AttrValue>,
+    pub evidence: Vec<AttrValue>,
+    pub color: Vec<usize>,
+}
+
+pub enum Msg {
+    SelectStep(usize),
+}
+
+pub struct FrequencyComponent {
+    /// The step the icon array is filtered to, or `None` for the last one.
+    step: Option<usize>,
+}
+
+fn step_label(step: &FrequencyStep, evidence: &[AttrValue]) -> String {
+    match step.evidence {
+        Some(ev_idx) => evidence[ev_idx].to_string(),
+        None => "Everyone".to_string(),
+    }
+}
+
+/// "Of the 108 people who ... : 9 have A, 99 have B."
+fn summary(step: &FrequencyStep, props: &FrequencyProps) -> Html {
+    let total: usize = step.people.iter().sum();
+    let parts = step.people.iter().enumerate().map(|(hyp_idx, people)| {
+        html! {
+            <span class={format!("e{}", props.color[hyp_idx])}>
+                {format!(" {} have {}.", people, props.hypotheses[hyp_idx])}
+            </span>
+        }
+    });
+    let lead = match step.evidence {
+        None => format!("Of {} people before any evidence:", POPULATION),
+        Some(_) => format!(
+            "Of {} people, {} would show every piece of evidence up to here:",
+            POPULATION, total
+        ),
+    };
+    html! {
+        <p class="frequency-summary">{lead}{for parts}</p>
+    }
+}
+
+fn icon_array(step: &FrequencyStep, first: &FrequencyStep, color: &[usize]) -> Html {
+    let mut icons = Vec::with_capacity(POPULATION);
+    for (hyp_idx, people) in first.people.iter().enumerate() {
+        for person in 0..*people {
+            let idx = icons.len();
+            let x = ICON_SIZE * (idx % ICON_COLUMNS) as f64 + ICON_SIZE / 2.0;
+            let y = ICON_SIZE * (idx / ICON_COLUMNS) as f64 + ICON_SIZE / 2.0;
+            let class = if person < step.people[hyp_idx] {
+                format!("icon e{}", color[hyp_idx])
+            } else {
+                format!("icon ruled-out e{}", color[hyp_idx])
+            };
+            icons.push(html! {
+                <circle class={class} cx={x.to_string()} cy={y.to_string()} r={(ICON_SIZE * 0.35).to_string()} />
+            });
+        }
+    }
+    let rows = icons.len().div_ceil(ICON_COLUMNS);
+    html! {
+        <svg class="icon-array" width={(ICON_SIZE * ICON_COLUMNS as f64).to_string()}
+            height={(ICON_SIZE * rows as f64).to_string()}>
+            {for icons}
+        </svg>
+    }
+}
+
+/// One hypothesis's branch from `step_idx` on: the people who show the next
+/// piece of evidence carry on down the tree, the rest stop.
+fn tree_branch(props: &FrequencyProps, hyp_idx: usize, step_idx: usize) -> Html {
+    let Some(next) = props.steps.get(step_idx + 1) else {
+        return html! {};
+    };
+    let before = props.steps[step_idx].people[hyp_idx];
+    let after = next.people[hyp_idx];
+    let label = step_label(next, &props.evidence);
+    html! {
+        <ul>
+            <li>
+                <span class={format!("frequency-node e{}", props.color[hyp_idx])}>
+                    {format!("{} show {}", after, label)}
+                </span>
+                {tree_branch(props, hyp_idx, step_idx + 1)}
+            </li>
+            <li>
+                <span class="frequency-node ruled-out">{format!("{} don't", before - after)}</span>
+            </li>
+        </ul>
+    }
+}
+
+fn frequency_tree(props: &FrequencyProps) -> Html {
+    let first = &props.steps[0];
+    let branches = props.hypotheses.iter().enumerate().map(|(hyp_idx, hyp)| {
+        html! {
+            <li>
+                <span class={format!("frequency-node e{}", props.color[hyp_idx])}>
+                    {format!("{} have {}", first.people[hyp_idx], hyp)}
+                </span>
+                {tree_branch(props, hyp_idx, 0)}
+            </li>
+        }
+    });
+    html! {
+        <ul class="frequency-tree">
+            <li>
+                <span class="frequency-node">{format!("{} people", POPULATION)}</span>
+                <ul>{for branches}</ul>
+            </li>
+        </ul>
+    }
+}
+
+impl Component for FrequencyComponent {
+    type Message = Msg;
+    type Properties = FrequencyProps;
+
+    fn create(_ctx: &yew::Context<Self>) -> Self {
+        Self { step: None }
+    }
+
+    fn update(&mut self, _ctx: &yew::Context<Self>, msg: Self::Message) -> bool {
+        match msg {
+            Msg::SelectStep(step_idx) => self.step = Some(step_idx),
+        }
+        true
+    }
+
+    fn view(&self, ctx: &yew::Context<Self>) -> Html {
+        let props = ctx.props();
+        let Some(last) = props.steps.len().checked_sub(1) else {
+            return html! {};
+        };
+        let step_idx = self.step.unwrap_or(last).min(last);
+        let step = &props.steps[step_idx];
+
+        match props.view {
+            FrequencyView::Hidden => html! {},
+            FrequencyView::Icons => {
+                let buttons = props.steps.iter().enumerate().map(|(idx, step)| {
+                    let onclick = ctx.link().callback(move |_e: MouseEvent| Msg::SelectStep(idx));
+                    html! {
+                        <button class={if idx == step_idx { "frequency-step selected" } else { "frequency-step" }}
+                            onclick={onclick}>
+                            {step_label(step, &props.evidence)}
+                        </button>
+                    }
+                });
+                html! {
+                    <div class="frequencies">
+                        <div class="frequency-steps">{for buttons}</div>
+                        {icon_array(step, &props.steps[0], &props.color)}
+                        {summary(step, props)}
+                    </div>
+                }
+            }
+            FrequencyView::Tree => html! {
+                <div class="frequencies">
+                    {frequency_tree(props)}
+                    {summary(&props.steps[last], props)}
+                </div>
+            },
+        }
+    }
+}
@@ -0,0 +1,43 @@
+// layout.rs
+// The first five color slots come from the palette in bayes.css; the rest are
+// generated here so any number of hypotheses gets a distinct color.
+const FIXED_COLORS: usize = 5;
+
+pub fn column_width(num_hypotheses: usize) -> usize {
+    if num_hypotheses <= FIXED_COLORS {
+        200
+    } else {
+        (1000 / num_hypotheses).max(120)
+    }
+}
+
+fn hue(slot: usize) -> f64 {
+    // Golden angle, so neighbouring slots land far apart on the color wheel.
+    (slot as f64 * 137.508) % 360.0
+}
+
+pub fn palette_css(num_colors: usize) -> String {
+    let mut light = String::new();
+    let mut dark = String::new();
+    let mut classes = String::new();
+    for slot in FIXED_COLORS..num_colors {
+        let h = hue(slot);
+        light += &format!(
+            "--bayes-{slot}: hsl({h:.0}, 65%, 75%); --bayes-{slot}-discard: hsla({h:.0}, 65%, 75%, 0.4); "
+        );
+        dark += &format!(
+            "--bayes-{slot}: hsl({h:.0}, 30%, 45%); --bayes-{slot}-discard: hsla({h:.0}, 30%, 45%, 0.4); "
+        );
+        classes += &format!(
+            ".b{slot} {{ background-color: var(--bayes-{slot}); }} \
+             .a{slot} {{ background-color: var(--bayes-{slot}-discard); }} \
+             .e{slot} {{ color: var(--bayes-{slot}); }} "
+        );
+    }
+    if classes.is_empty() {
+        return classes;
+    }
+    format!(
+        ":root {{ {light}}} @media (prefers-color-scheme: dark) {{ :root {{ {dark}}} }} {classes}"
+    )
+}
@@ -0,0 +1,146 @@
+// library_component.rs
+use crate::label_component::LabelCallback;
+use crate::LabelComponent;
+use bayes_core::Document;
+use wasm_bindgen::JsValue;
+use web_sys::HtmlSelectElement;
+use yew::prelude::*;
+use yew::virtual_dom::AttrValue;
+
+#[derive(Properties, PartialEq)]
+pub struct LibraryProps {
+    pub documents: Vec<Document>,
+    pub current: Option<u64>,
+    pub hypotheses: Vec<AttrValue>,
+    /// The true hypothesis, if the current analysis has been resolved.
+    pub resolved: Option<usize>,
+    pub onchange: Callback<LibraryCallback>,
+}
+
+pub enum LibraryCallback {
+    Switch(Option<u64>),
+    New,
+    Rename(String),
+    Duplicate,
+    Delete,
+    Resolve(Option<usize>),
+}
+
+pub enum Msg {
+    Switch(Option<u64>),
+    New,
+    Rename(String),
+    Duplicate,
+    Delete,
+    Resolve(Option<usize>),
+}
+
+pub struct LibraryComponent {}
+
+fn format_timestamp(ms: f64) -> String {
+    js_sys::Date::new(&JsValue::from_f64(ms))
+        .to_locale_string("default", &JsValue::UNDEFINED)
+        .into()
+}
+
+impl Component for LibraryComponent {
+    type Message = Msg;
+    type Properties = LibraryProps;
+
+    fn create(_ctx: &yew::Context<Self>) -> Self {
+        Self {}
+    }
+
+    fn update(&mut self, ctx: &yew::Context<Self>, msg: Self::Message) -> bool {
+        let onchange = ctx.props().onchange.clone();
+        match msg {
+            Msg::Switch(id) => onchange.emit(LibraryCallback::Switch(id)),
+            Msg::New => onchange.emit(LibraryCallback::New),
+            Msg::Rename(name) => onchange.emit(LibraryCallback::Rename(name)),
+            Msg::Duplicate => onchange.emit(LibraryCallback::Duplicate),
+            Msg::Resolve(truth) => onchange.emit(LibraryCallback::Resolve(truth)),
+            Msg::Delete => {
+                let confirmed = web_sys::window()
+                    .unwrap()
+                    .confirm_with_message("Delete this analysis? This can't be undone.")
+                    .unwrap_or(false);
+                if confirmed {
+                    onchange.emit(LibraryCallback::Delete);
+                }
+            }
+        }
+        false
+    }
+
+    fn view(&self, ctx: &yew::Context<Self>) -> Html {
+        let current = ctx.props().current;
+
+        let onchange_select = ctx.link().callback(|e: Event| {
+            let select: HtmlSelectElement = e.target_unchecked_into();
+            Msg::Switch(select.value().parse::<u64>().ok())
+        });
+        let onchange_resolve = ctx.link().callback(|e: Event| {
+            let select: HtmlSelectElement = e.target_unchecked_into();
+            Msg::Resolve(select.value().parse::<usize>().ok())
+        });
+        let onclick_new = ctx.link().callback(|_e: MouseEvent| Msg::New);
+        let onclick_duplicate = ctx.link().callback(|_e: MouseEvent| Msg::Duplicate);
+        let onchange_name =
+            ctx.link()
+                .callback(move |label_change: LabelCallback| match label_change {
+                    LabelCallback::Delete => Msg::Delete,
+                    LabelCallback::LabelEdit(label) => Msg::Rename(label),
+                });
+
+        let display_options = ctx.props().documents.iter().map(|doc| {
+            html! {
+                <option value={doc.id.to_string()} selected={current == Some(doc.id)}>
+                    {doc.name.clone()}
+                </option>
+            }
+        });
+
+        let resolved = ctx.props().resolved;
+        let display_truths = ctx
+            .props()
+            .hypotheses
+            .iter()
+            .enumerate()
+            .map(|(hyp_idx, hyp)| {
+                html! {
+                    <option value={hyp_idx.to_string()} selected={resolved == Some(hyp_idx)}>
+                        {format!("Resolved: {} was true", hyp)}
+                    </option>
+                }
+            });
+
+        let current_doc =
+            current.and_then(|id| ctx.props().documents.iter().find(|doc| doc.id == id));
+
+        html! {
+            <div class="library">
+                <select class="library-picker" onchange={onchange_select}>
+                    <option value="scratch" selected={current.is_none()}>{"Scratch (this tab only)"}</option>
+                    {for display_options}
+                </select>
+                if let Some(doc) = current_doc {
+                    <LabelComponent
+                        class={AttrValue::from("library-name")}
+                        placeholder={AttrValue::from(doc.name.clone())}
+                        onchange={onchange_name}
+                        deleteable={true}
+                    />
+                    <div class="library-modified">{format!("Saved {}", format_timestamp(doc.modified))}</div>
+                    <select class="library-resolve" onchange={onchange_resolve}>
+                        <option value="unresolved" selected={resolved.is_none()}>{"Unresolved"}</option>
+                        {for display_truths}
+                    </select>
+                }
+                <div class="library-buttons">
+                    <button onclick={onclick_new}>{"New"}</button>
+                    <button onclick={onclick_duplicate}>{"Duplicate"}</button>
+                </div>
+            </div>
+        }
+    }
+}
@@ -8,15 +8,24 @@ use num_component::NumComponent;
 use yew::prelude::*;
 
 mod bayes_component;
+mod calibration_component;
 mod chance_component;
+mod decision_component;
+mod diagnostics_component;
 mod evidence_component;
+mod frequency_component;
 mod json_crush;
 mod label_component;
+mod layout;
+mod library_component;
 mod modal_component;
 mod num_component;
+mod sensitivity_component;
 mod share_component;
 mod slider_component;
+mod spread_component;
 mod storage;
+mod trajectory_component;
 
 #[function_component(App)]
 fn app() -> Html {
@@ -1,10 +1,13 @@
+use bayes_core::parse_quantity;
 use web_sys::HtmlInputElement;
 use yew::prelude::*;
 use yew::virtual_dom::AttrValue;
 
 pub enum Msg {
-    SetInput(f64),
-    SoftInput(f64),
+    SoftInput(f64, Option<String>),
+    Invalid(String),
+    /// Text that isn't a number yet but may become one, such as a lone `-`.
+    Unfinished,
     Blank,
     Blur,
     DoNothing,
@@ -20,6 +23,9 @@ pub struct NumProps {
     pub onchange: Callback<f64>,
     #[prop_or(false)]
     pub display_only: bool,
+    /// Whether values are percentages, so that e.g. `3:1` means 75 rather than 0.75.
+    #[prop_or(false)]
+    pub percent: bool,
 }
 
 #[derive(Debug)]
@@ -28,6 +34,9 @@ pub struct NumComponent {
     value: f64,
     is_blank: bool,
     editing: bool,
+    /// What the text being typed works out to, when it isn't a plain number.
+    preview: Option<String>,
+    error: Option<String>,
 }
 
 fn is_int_value(num: f64) -> bool {
@@ -35,6 +44,22 @@ fn is_int_value(num: f64) -> bool {
     (num - rounded).abs() <= std::f64::EPSILON
 }
 
+fn format_preview(num: f64) -> String {
+    if is_int_value(num) {
+        num.round().to_string()
+    } else {
+        let fixed = format!("{:.6}", num);
+        fixed
+            .trim_end_matches('0')
+            .trim_end_matches('.')
+            .to_string()
+    }
+}
+
+fn is_unfinished(text: &str) -> bool {
+    matches!(text.trim(), "-" | "+" | "." | "-." | "+.")
+}
+
 fn is_slider_value(num: f64) -> bool {
     let rounded = (num * 10.0).round() / 10.0;
     (num - rounded).abs() <= std::f64::EPSILON
@@ -50,6 +75,8 @@ impl Component for NumComponent {
             value: ctx.props().force_value.unwrap_or(1.0),
             is_blank: false,
             editing: false,
+            preview: None,
+            error: None,
         }
     }
 
@@ -58,33 +85,31 @@ impl Component for NumComponent {
         let class = &ctx.props().class;
         let onblur = ctx.link().callback(|_: FocusEvent| Msg::Blur);
 
+        let percent = ctx.props().percent;
         let oninput = ctx.link().callback(move |e: InputEvent| {
             let input_el: HtmlInputElement = e.target_unchecked_into();
-            let cursor_position = input_el.selection_start().unwrap_or(Some(0));
             let val_str = input_el.value();
-
-            let msg = match val_str.parse() {
-                Ok(val) => Msg::SoftInput(val),
-                Err(_) => match val_str.as_str() {
-                    "" => Msg::Blank,
-                    _ if val_str.matches('.').count() == 1
-                        && val_str.chars().all(|c| c.is_numeric() || c == '.') =>
-                    {
-                        Msg::SoftInput(cur_val)
-                    }
-                    _ => Msg::SetInput(cur_val),
-                },
-            };
-
-            if let Msg::SetInput(_) = msg {
-                if let Some(pos) = cursor_position {
-                    gloo_timers::callback::Timeout::new(0, move || {
-                        input_el.set_selection_range(pos, pos).ok();
-                    })
-                    .forget();
+            if val_str.trim().is_empty() {
+                return Msg::Blank;
+            }
+            if is_unfinished(&val_str) {
+                return Msg::Unfinished;
+            }
+            match parse_quantity(&val_str) {
+                Ok(quantity) => {
+                    let val = quantity.value(percent);
+                    // Plain numbers need no preview.
+                    let preview = val_str.trim().parse::<f64>().is_err().then(|| {
+                        format!(
+                            "= {}{}",
+                            format_preview(val),
+                            if percent { "%" } else { "" }
+                        )
+                    });
+                    Msg::SoftInput(val, preview)
                 }
+                Err(message) => Msg::Invalid(message),
             }
-            msg
         });
 
         let onkeydown = ctx.link().callback(|e: KeyboardEvent| {
@@ -101,7 +126,12 @@ impl Component for NumComponent {
 
         let invalid = ctx.props().min_value.map_or(false, |min| min > cur_val)
             || ctx.props().max_value.map_or(false, |max| max < cur_val);
-        let class_str = class.to_string() + if invalid { " invalid" } else { "" };
+        let class_str = class.to_string()
+            + if invalid || self.error.is_some() {
+                " invalid"
+            } else {
+                ""
+            };
 
         let val = ctx.props().force_value.unwrap_or(self.value);
 
@@ -126,9 +156,16 @@ impl Component for NumComponent {
                 format_num::format_num!("#.1f", val)
             };
             html! {
-                <input ref={input_ref} class={class_str}
-                      value={value} onblur={onblur} onkeydown={onkeydown}
-                    type="text" {oninput} placeholder={placeholder} />
+                <>
+                    <input ref={input_ref} class={class_str}
+                          value={value} onblur={onblur} onkeydown={onkeydown}
+                        type="text" {oninput} placeholder={placeholder} />
+                    if let Some(error) = &self.error {
+                        <div class="num-message num-error">{error}</div>
+                    } else if let Some(preview) = &self.preview {
+                        <div class="num-message num-preview">{preview}</div>
+                    }
+                </>
             }
         }
     }
@@ -149,28 +186,40 @@ impl Component for NumComponent {
     fn update(&mut self, ctx: &yew::Context<Self>, msg: Self::Message) -> bool {
         self.is_blank = false;
         match msg {
-            Msg::SetInput(val) => {
+            Msg::SoftInput(val, preview) => {
                 self.value = val;
-                self.editing = false;
-                self.is_blank = false;
+                self.editing = true;
+                self.is_blank = true;
+                self.preview = preview;
+                self.error = None;
                 ctx.props().onchange.emit(val);
                 true
             }
-            Msg::SoftInput(val) => {
-                self.value = val;
+            Msg::Invalid(message) => {
+                // Keep what was typed so it can be fixed, but don't emit it.
                 self.editing = true;
-                self.is_blank = true;
-                ctx.props().onchange.emit(val);
+                self.preview = None;
+                self.error = Some(message);
                 true
             }
             Msg::Blur => {
                 self.editing = false;
+                self.preview = None;
+                self.error = None;
                 if let Some(input_el) = self.input_ref.cast::<HtmlInputElement>() {
                     let _ = input_el.blur();
                 }
                 true
             }
+            Msg::Unfinished => {
+                self.editing = true;
+                self.preview = None;
+                self.error = None;
+                true
+            }
             Msg::Blank => {
+                self.preview = None;
+                self.error = None;
                 self.value = 1.0;
                 self.is_blank = true;
                 self.editing = true;
@@ -0,0 +1,157 @@
+// sensitivity_component.rs
+use bayes_core::{Input, Sensitivity};
+use web_sys::HtmlSelectElement;
+use yew::prelude::*;
+use yew::virtual_dom::AttrValue;
+
+/// Inputs shown before "Show all" is clicked.
+const TOP_INPUTS: usize = 10;
+
+#[derive(Properties, PartialEq)]
+pub struct SensitivityProps {
+    pub sensitivities: Vec<Sensitivity>,
+    pub posterior: Vec<f64>,
+    pub hypotheses: Vec<AttrValue>,
+    pub evidence: Vec<AttrValue>,
+    pub color: Vec<usize>,
+    /// How far each input was nudged, as a fraction.
+    pub delta: f64,
+}
+
+pub enum Msg {
+    Focus(usize),
+    ToggleShowAll,
+}
+
+pub struct SensitivityComponent {
+    focus: Option<usize>,
+    show_all: bool,
+}
+
+fn input_label(input: Input, props: &SensitivityProps) -> String {
+    match input {
+        Input::Prior(hyp_idx) => format!("Prior · {}", props.hypotheses[hyp_idx]),
+        Input::Likelihood(ev_idx, hyp_idx) => {
+            format!("{} · {}", props.evidence[ev_idx], props.hypotheses[hyp_idx])
+        }
+    }
+}
+
+fn derivative_label(input: Input, derivative: f64) -> String {
+    match input {
+        Input::Prior(_) => format!("{:+.2} points per unit of prior odds", derivative),
+        Input::Likelihood(..) => {
+            format!("{:+.2} points per point of likelihood", derivative * 0.01)
+        }
+    }
+}
+
+impl Component for SensitivityComponent {
+    type Message = Msg;
+    type Properties = SensitivityProps;
+
+    fn create(_ctx: &yew::Context<Self>) -> Self {
+        Self {
+            focus: None,
+            show_all: false,
+        }
+    }
+
+    fn update(&mut self, _ctx: &yew::Context<Self>, msg: Self::Message) -> bool {
+        match msg {
+            Msg::Focus(hyp_idx) => self.focus = Some(hyp_idx),
+            Msg::ToggleShowAll => self.show_all = !self.show_all,
+        }
+        true
+    }
+
+    fn view(&self, ctx: &yew::Context<Self>) -> Html {
+        let props = ctx.props();
+        if props.hypotheses.is_empty() {
+            return html! {};
+        }
+        // Default to whichever hypothesis currently leads.
+        let focus = self
+            .focus
+            .filter(|idx| *idx < props.hypotheses.len())
+            .unwrap_or_else(|| {
+                props
+                    .posterior
+                    .iter()
+                    .enumerate()
+                    .fold(0, |best, (idx, p)| {
+                        if *p > props.posterior[best] {
+                            idx
+                        } else {
+                            best
+                        }
+                    })
+            });
+        let base = props.posterior[focus];
+
+        let mut ranked: Vec<&Sensitivity> = props.sensitivities.iter().collect();
+        ranked.sort_by(|a, b| {
+            (b.high[focus] - b.low[focus])
+                .abs()
+                .total_cmp(&(a.high[focus] - a.low[focus]).abs())
+        });
+        let total = ranked.len();
+        if !self.show_all {
+            ranked.truncate(TOP_INPUTS);
+        }
+
+        // Bars are scaled so the widest one fills half the track on each side.
+        let max_dev = ranked
+            .iter()
+            .map(|s| {
+                (s.low[focus] - base)
+                    .abs()
+                    .max((s.high[focus] - base).abs())
+            })
+            .fold(0.0, f64::max)
+            .max(1e-9);
+        let position = |value: f64| 50.0 + 50.0 * (value - base) / max_dev;
+
+        let onchange_focus = ctx.link().callback(|e: Event| {
+            let select: HtmlSelectElement = e.target_unchecked_into();
+            Msg::Focus(select.value().parse().unwrap_or(0))
+        });
+        let onclick_show_all = ctx.link().callback(|_e: MouseEvent| Msg::ToggleShowAll);
+
+        let display_options = props.hypotheses.iter().enumerate().map(|(idx, hyp)| {
+            html! { <option value={idx.to_string()} selected={idx == focus}>{hyp.clone()}</option> }
+        });
+
+        let display_rows = ranked.iter().map(|s| {
+            let (low, high) = (s.low[focus], s.high[focus]);
+            let (left, right) = (position(low.min(high)), position(low.max(high)));
+            html! {
+                <div class="tornado-row" title={derivative_label(s.input, s.derivative[focus])}>
+                    <div class="tornado-label">{input_label(s.input, props)}</div>
+                    <div class="tornado-track">
+                        <div class={format!("tornado-bar b{}", props.color[focus])}
+                            style={format!("left:{}%; width:{}%", left, right - left)}></div>
+                        <div class="tornado-base"></div>
+                    </div>
+                    <div class="tornado-value">{format!("{:.1}% – {:.1}%", low, high)}</div>
+                </div>
+            }
+        });
+
+        html! {
+            <div class="sensitivity">
+                <div class="sensitivity-header">
+                    {"Posterior of "}
+                    <select onchange={onchange_focus}>{for display_options}</select>
+                    {format!(" ({:.1}%) when each input moves ±{}%", base, props.delta * 100.0)}
+                </div>
+                {for display_rows}
+                if total > TOP_INPUTS {
+                    <button class="sensitivity-more" onclick={onclick_show_all}>
+                        {if self.show_all { "Show fewer".to_string() } else { format!("Show all {} inputs", total) }}
+                    </button>
+                }
+            </div>
+        }
+    }
+}
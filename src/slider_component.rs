@@ -102,7 +102,7 @@ impl Component for SliderComponent {
                 </div>
                 <NumComponent min_value={0.0} max_value={100.0}
                 force_value={Some(self.likelihood*100.0)} class={AttrValue::from("like")}
-                placeholder={AttrValue::from("50")} onchange={&onchange_odds}
+                placeholder={AttrValue::from("50")} onchange={&onchange_odds} percent={true}
                 />
                 <div class="percent-symbol">
                 <button class="no_button" >{"%"}</button>
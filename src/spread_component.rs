@@ -0,0 +1,103 @@
+// spread_component.rs
+use bayes_core::Spread;
+use web_sys::HtmlInputElement;
+use yew::prelude::*;
+
+#[derive(Properties, PartialEq)]
+pub struct SpreadProps {
+    pub spread: Option<Spread>,
+    /// Range bounds are shown divided by this, e.g. 0.01 for percentages.
+    pub scale: f64,
+    #[prop_or(false)]
+    pub percent: bool,
+    pub onchange: Callback<Option<Spread>>,
+}
+
+pub enum Msg {
+    Input(String),
+    Commit,
+    DoNothing,
+}
+
+pub struct SpreadComponent {
+    text: String,
+    error: Option<String>,
+}
+
+fn format_spread(props: &SpreadProps) -> String {
+    props
+        .spread
+        .map(|spread| spread.format(props.scale, props.percent))
+        .unwrap_or_default()
+}
+
+impl Component for SpreadComponent {
+    type Message = Msg;
+    type Properties = SpreadProps;
+
+    fn create(ctx: &yew::Context<Self>) -> Self {
+        Self {
+            text: format_spread(ctx.props()),
+            error: None,
+        }
+    }
+
+    fn changed(&mut self, ctx: &yew::Context<Self>) -> bool {
+        if self.error.is_none() {
+            self.text = format_spread(ctx.props());
+        }
+        true
+    }
+
+    fn update(&mut self, ctx: &yew::Context<Self>, msg: Self::Message) -> bool {
+        match msg {
+            Msg::Input(text) => {
+                self.text = text;
+                false
+            }
+            Msg::Commit => {
+                match Spread::parse(&self.text, ctx.props().scale) {
+                    Ok(spread) => {
+                        self.error = None;
+                        ctx.props().onchange.emit(spread);
+                    }
+                    Err(e) => self.error = Some(e),
+                }
+                true
+            }
+            Msg::DoNothing => false,
+        }
+    }
+
+    fn view(&self, ctx: &yew::Context<Self>) -> Html {
+        let oninput = ctx.link().callback(|e: InputEvent| {
+            let input_el: HtmlInputElement = e.target_unchecked_into();
+            Msg::Input(input_el.value())
+        });
+        let onblur = ctx.link().callback(|_: FocusEvent| Msg::Commit);
+        let onkeydown = ctx.link().callback(|e: KeyboardEvent| {
+            if e.key() == "Enter" {
+                e.prevent_default();
+                Msg::Commit
+            } else {
+                Msg::DoNothing
+            }
+        });
+        let placeholder = if ctx.props().percent {
+            "± e.g. 40-60%"
+        } else {
+            "± e.g. 1-3"
+        };
+        let class = if self.error.is_some() {
+            "spread invalid"
+        } else {
+            "spread"
+        };
+
+        html! {
+            <input type="text" class={class} value={self.text.clone()} placeholder={placeholder}
+                title={self.error.clone().unwrap_or_else(|| "A range, or Beta(a, b)".to_string())}
+                {oninput} {onblur} {onkeydown} />
+        }
+    }
+}
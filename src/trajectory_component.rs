@@ -0,0 +1,285 @@
+// trajectory_component.rs
+use bayes_core::{log_odds_in_db, percentize, recalculate_to, StrengthUnit};
+use gloo::utils::document;
+use yew::prelude::*;
+use yew::virtual_dom::AttrValue;
+
+const CHART_WIDTH: f64 = 600.0;
+const CHART_HEIGHT: f64 = 200.0;
+const MARGIN_LEFT: f64 = 45.0;
+const MARGIN_BOTTOM: f64 = 20.0;
+/// Log-odds beyond this many decibels (e.g. a ruled-out hypothesis) are drawn
+/// at the edge of the chart.
+const LOG_ODDS_LIMIT: f64 = 40.0;
+
+#[derive(Properties, PartialEq)]
+pub struct TrajectoryProps {
+    pub prior_odds: Vec<f64>,
+    /// With inactive and planned rows as 1s, as from `effective_likelihoods`.
+    pub likelihoods: Vec<Vec<f64>>,
+    pub hypotheses: Vec<AttrValue>,
+    pub evidence: Vec<AttrValue>,
+    pub counted: Vec<bool>,
+    pub color: Vec<usize>,
+    pub unit: StrengthUnit,
+}
+
+/// How the posterior is drawn across the evidence steps.
+#[derive(Clone, Copy, PartialEq)]
+pub enum ChartStyle {
+    Lines,
+    Stacked,
+    LogOdds,
+}
+
+pub enum Msg {
+    Style(ChartStyle),
+    Hover(Option<usize>),
+    Select(usize),
+}
+
+pub struct TrajectoryComponent {
+    style: ChartStyle,
+    hovered: Option<usize>,
+}
+
+/// The evidence row for step `step` of the chart; step 0 is the prior.
+fn evidence_row(step: usize) -> Option<web_sys::Element> {
+    step.checked_sub(1)
+        .and_then(|ev_idx| document().get_element_by_id(&format!("evidence-{}", ev_idx)))
+}
+
+fn highlight_row(step: Option<usize>, highlighted: bool) {
+    if let Some(row) = step.and_then(evidence_row) {
+        row.class_list()
+            .toggle_with_force("highlighted", highlighted)
+            .ok();
+    }
+}
+
+impl TrajectoryComponent {
+    /// The log-odds unit, which for plain ratios falls back to decibels.
+    fn log_unit(unit: StrengthUnit) -> StrengthUnit {
+        if unit == StrengthUnit::Ratio {
+            StrengthUnit::Decibels
+        } else {
+            unit
+        }
+    }
+}
+
+impl Component for TrajectoryComponent {
+    type Message = Msg;
+    type Properties = TrajectoryProps;
+
+    fn create(_ctx: &yew::Context<Self>) -> Self {
+        Self {
+            style: ChartStyle::Lines,
+            hovered: None,
+        }
+    }
+
+    fn update(&mut self, _ctx: &yew::Context<Self>, msg: Self::Message) -> bool {
+        match msg {
+            Msg::Style(style) => self.style = style,
+            Msg::Hover(step) => {
+                highlight_row(self.hovered, false);
+                highlight_row(step, true);
+                self.hovered = step;
+            }
+            Msg::Select(step) => {
+                if let Some(row) = evidence_row(step) {
+                    row.scroll_into_view_with_bool(false);
+                }
+                return false;
+            }
+        }
+        true
+    }
+
+    fn destroy(&mut self, _ctx: &yew::Context<Self>) {
+        highlight_row(self.hovered, false);
+    }
+
+    fn view(&self, ctx: &yew::Context<Self>) -> Html {
+        let props = ctx.props();
+        let steps = props.likelihoods.len() + 1;
+        // Percent after each step, one row per step.
+        let trajectory: Vec<Vec<f64>> = (0..steps)
+            .map(|to| {
+                percentize(recalculate_to(
+                    props.prior_odds.clone(),
+                    props.likelihoods.clone(),
+                    to,
+                ))
+            })
+            .collect();
+        let log_unit = Self::log_unit(props.unit);
+
+        let values: Vec<Vec<f64>> = match self.style {
+            ChartStyle::LogOdds => trajectory
+                .iter()
+                .map(|posterior| {
+                    log_odds_in_db(posterior.clone())
+                        .iter()
+                        .map(|db| db.clamp(-LOG_ODDS_LIMIT, LOG_ODDS_LIMIT))
+                        .collect()
+                })
+                .collect(),
+            _ => trajectory.clone(),
+        };
+        // Log-odds are plotted in decibels and only labelled in `log_unit`,
+        // which is the same scale stretched.
+        let (low, high) = match self.style {
+            ChartStyle::LogOdds => {
+                let max = values
+                    .iter()
+                    .flatten()
+                    .filter(|v| v.is_finite())
+                    .fold(10.0f64, |max, v| max.max(v.abs()));
+                (-max, max)
+            }
+            _ => (0.0, 100.0),
+        };
+
+        let plot_width = CHART_WIDTH - MARGIN_LEFT;
+        let plot_height = CHART_HEIGHT - MARGIN_BOTTOM;
+        let step_width = plot_width / (steps.max(2) - 1) as f64;
+        let to_x = move |step: usize| MARGIN_LEFT + step_width * step as f64;
+        let to_y = move |v: f64| {
+            let v = if v.is_finite() { v } else { 0.0 };
+            plot_height * (1.0 - (v - low) / (high - low))
+        };
+
+        let hyps = 0..props.hypotheses.len();
+        let shapes: Vec<Html> = match self.style {
+            ChartStyle::Stacked => {
+                // Each hypothesis is the band between the running totals before and after it.
+                let mut below = vec![0.0; steps];
+                hyps.map(|hyp_idx| {
+                    let above: Vec<f64> = below
+                        .iter()
+                        .zip(&values)
+                        .map(|(b, step)| b + step[hyp_idx])
+                        .collect();
+                    let mut points: Vec<String> = (0..steps)
+                        .map(|step| format!("{:.1},{:.1}", to_x(step), to_y(above[step])))
+                        .collect();
+                    points.extend(
+                        (0..steps)
+                            .rev()
+                            .map(|step| format!("{:.1},{:.1}", to_x(step), to_y(below[step]))),
+                    );
+                    below = above;
+                    html! {
+                        <polygon class={format!("trajectory-area e{}", props.color[hyp_idx])} points={points.join(" ")} />
+                    }
+                })
+                .collect()
+            }
+            _ => hyps
+                .map(|hyp_idx| {
+                    let points: Vec<String> = (0..steps)
+                        .map(|step| {
+                            format!("{:.1},{:.1}", to_x(step), to_y(values[step][hyp_idx]))
+                        })
+                        .collect();
+                    html! {
+                        <path class={format!("trajectory-line e{}", props.color[hyp_idx])} d={format!("M{}", points.join(" L"))} />
+                    }
+                })
+                .collect(),
+        };
+
+        let axis_label = |v: f64| match self.style {
+            ChartStyle::LogOdds => log_unit.format(v),
+            _ => format!("{:.0}%", v),
+        };
+        let ticks = [low, (low + high) / 2.0, high].map(|v| {
+            html! {
+                <>
+                    <line class="trajectory-grid" x1={MARGIN_LEFT.to_string()} x2={CHART_WIDTH.to_string()}
+                        y1={to_y(v).to_string()} y2={to_y(v).to_string()} />
+                    <text class="trajectory-axis" x={(MARGIN_LEFT - 4.0).to_string()} y={(to_y(v) + 4.0).to_string()}
+                        text-anchor="end">{axis_label(v)}</text>
+                </>
+            }
+        });
+
+        // One hover zone per step, as wide as the gap between steps.
+        let zones = (0..steps).map(|step| {
+            let onmouseenter = ctx.link().callback(move |_e: MouseEvent| Msg::Hover(Some(step)));
+            let onclick = ctx.link().callback(move |_e: MouseEvent| Msg::Select(step));
+            let counted = step == 0 || props.counted.get(step - 1).copied().unwrap_or(true);
+            let label = match step {
+                0 => "Prior".to_string(),
+                _ => props.evidence[step - 1].to_string(),
+            };
+            html! {
+                <>
+                    <text class={if counted { "trajectory-step" } else { "trajectory-step not-counted" }}
+                        x={to_x(step).to_string()} y={(CHART_HEIGHT - 4.0).to_string()} text-anchor="middle">
+                        {if step == 0 { "P".to_string() } else { step.to_string() }}
+                        <title>{label}</title>
+                    </text>
+                    <rect class="trajectory-zone" x={(to_x(step) - step_width / 2.0).to_string()} y="0"
+                        width={step_width.to_string()} height={plot_height.to_string()}
+                        onmouseenter={onmouseenter} onclick={onclick} />
+                </>
+            }
+        });
+
+        let hover = self.hovered.filter(|step| *step < steps).map(|step| {
+            let label = match step {
+                0 => "Prior".to_string(),
+                _ => props.evidence[step - 1].to_string(),
+            };
+            let readings = props.hypotheses.iter().enumerate().map(|(hyp_idx, hyp)| {
+                let reading = match self.style {
+                    ChartStyle::LogOdds => log_unit.format(values[step][hyp_idx]),
+                    _ => format!("{:.1}%", trajectory[step][hyp_idx]),
+                };
+                html! {
+                    <span class={format!("e{}", props.color[hyp_idx])}>{format!(" {}: {}", hyp, reading)}</span>
+                }
+            });
+            (
+                html! {
+                    <line class="trajectory-cursor" x1={to_x(step).to_string()} x2={to_x(step).to_string()}
+                        y1="0" y2={plot_height.to_string()} />
+                },
+                html! {
+                    <div class="trajectory-reading"><b>{label}</b>{for readings}</div>
+                },
+            )
+        });
+        let (cursor, reading) = hover.unwrap_or_else(|| (html! {}, html! {}));
+
+        let onmouseleave = ctx.link().callback(|_e: MouseEvent| Msg::Hover(None));
+        let style_button = |style: ChartStyle, label: &'static str| {
+            let onclick = ctx.link().callback(move |_e: MouseEvent| Msg::Style(style));
+            html! {
+                <button class={if self.style == style { "trajectory-style selected" } else { "trajectory-style" }}
+                    onclick={onclick}>{label}</button>
+            }
+        };
+
+        html! {
+            <div class="trajectory">
+                <div class="trajectory-styles">
+                    {style_button(ChartStyle::Lines, "Lines")}
+                    {style_button(ChartStyle::Stacked, "Stacked")}
+                    {style_button(ChartStyle::LogOdds, "Log-odds")}
+                </div>
+                <svg class="trajectory-chart" width={CHART_WIDTH.to_string()} height={CHART_HEIGHT.to_string()}
+                    onmouseleave={onmouseleave}>
+                    {for ticks}
+                    {for shapes}
+                    {cursor}
+                    {for zones}
+                </svg>
+                {reading}
+            </div>
+        }
+    }
+}